@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+
+/// Turned with the camera so that it stays upright when the board is flipped.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Upright;
+
+/// The button of the move list panel that flips the board.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct FlipButton;
@@ -11,6 +11,43 @@ pub enum PieceType {
     King,
 }
 
+impl PieceType {
+    pub const PROMOTIONS: [PieceType; 4] = [
+        PieceType::Queen,
+        PieceType::Rook,
+        PieceType::Bishop,
+        PieceType::Knight,
+    ];
+
+    pub fn is_promotion(self) -> bool {
+        Self::PROMOTIONS.contains(&self)
+    }
+
+    /// The upper case letter used for this piece in move notation.
+    pub fn to_char(self) -> char {
+        match self {
+            PieceType::Pawn => 'P',
+            PieceType::Knight => 'N',
+            PieceType::Bishop => 'B',
+            PieceType::Rook => 'R',
+            PieceType::Queen => 'Q',
+            PieceType::King => 'K',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'P' => Some(PieceType::Pawn),
+            'N' => Some(PieceType::Knight),
+            'B' => Some(PieceType::Bishop),
+            'R' => Some(PieceType::Rook),
+            'Q' => Some(PieceType::Queen),
+            'K' => Some(PieceType::King),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Reflect, Component, Debug, Clone, Copy, PartialEq, Eq)]
 #[reflect(Component)]
 pub enum ChessPiece {
@@ -64,6 +101,12 @@ impl ChessPiece {
 }
 
 impl ChessPiece {
+    pub fn with_type(self, piece_type: PieceType) -> Self {
+        match self {
+            ChessPiece::White(_) => ChessPiece::White(piece_type),
+            ChessPiece::Black(_) => ChessPiece::Black(piece_type),
+        }
+    }
     pub fn piece_type(self) -> PieceType {
         match self {
             ChessPiece::White(piece) => piece,
@@ -76,14 +119,19 @@ impl ChessPiece {
     pub fn is_white(self) -> bool {
         matches!(self, ChessPiece::White(_))
     }
+    pub fn is_opponent(self, other: ChessPiece) -> bool {
+        self.is_black() != other.is_black()
+    }
+    /// Material worth in hundredths of a pawn. Kings are never captured, so
+    /// they count for nothing here and mate is scored by the search instead.
     pub fn value(self) -> i32 {
         match self.piece_type() {
-            PieceType::Pawn => 2,
-            PieceType::Knight => 6,
-            PieceType::Bishop => 7,
-            PieceType::Rook => 10,
-            PieceType::Queen => 18,
-            PieceType::King => i32::MAX,
+            PieceType::Pawn => 100,
+            PieceType::Knight => 300,
+            PieceType::Bishop => 350,
+            PieceType::Rook => 500,
+            PieceType::Queen => 900,
+            PieceType::King => 0,
         }
     }
     pub fn image_index(self) -> usize {
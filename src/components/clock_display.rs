@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+
+use crate::data::Turn;
+
+/// The box showing one side's remaining time.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct ClockDisplay(pub Turn);
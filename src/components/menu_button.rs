@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+
+/// A button of the main menu, pause menu or game over screen.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum MenuButton {
+    Play,
+    Opponent,
+    AutoOrient,
+    TimeControl,
+    Difficulty,
+    Resume,
+    Rematch,
+    MainMenu,
+}
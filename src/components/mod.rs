@@ -1,2 +1,17 @@
 mod chess_piece;
 pub use self::chess_piece::*;
+
+mod promotion_picker;
+pub use self::promotion_picker::*;
+
+mod menu_button;
+pub use self::menu_button::*;
+
+mod move_list;
+pub use self::move_list::*;
+
+mod board_view;
+pub use self::board_view::*;
+
+mod clock_display;
+pub use self::clock_display::*;
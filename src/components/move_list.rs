@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+/// The node holding the rows of the move list panel.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct MoveList;
+
+/// A move of the move list, showing the position after the first `0` moves
+/// when clicked.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct MoveListEntry(pub usize);
+
+/// A button under the move list that takes back or replays moves.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum HistoryButton {
+    Undo,
+    Redo,
+}
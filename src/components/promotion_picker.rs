@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+use crate::components::PieceType;
+
+/// The root node of the promotion chooser.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct PromotionPicker;
+
+/// A button of the promotion chooser that promotes to its piece.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct PromotionButton(pub PieceType);
+
+/// The button of the promotion chooser that takes the pawn back.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct PromotionCancel;
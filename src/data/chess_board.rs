@@ -1,101 +1,89 @@
 use bevy::{prelude::*, utils::HashMap};
-use hexx::{shapes, Hex, HexLayout};
+use hexx::HexLayout;
 
-use crate::components::ChessPiece;
+use crate::{
+    components::{ChessPiece, PieceType},
+    data::{
+        zobrist::{en_passant_key, piece_key, BLACK_TO_MOVE_KEY},
+        ChessMove, Square,
+    },
+};
 
-const GLINSKI_COLUMNS: [char; 11] = ['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'K', 'L'];
-const GLINSKI_RADIUS: u32 = 5;
-const GLINSKI_COLUMN_OFFSET: i32 = GLINSKI_RADIUS as i32;
-const GLINSKI_ROW_OFFSET: i32 = GLINSKI_RADIUS as i32 + 1;
+/// Everything needed to take back a move played on a [`ChessBoard`].
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveUndo {
+    pub from: Square,
+    pub to: Square,
+    /// The moving piece before any promotion.
+    pub piece: ChessPiece,
+    pub captured: Option<ChessPiece>,
+    /// Differs from `to` when capturing en passant.
+    pub captured_square: Square,
+    /// The en passant square before the move.
+    pub en_passant: Option<Square>,
+}
 
-#[derive(Reflect)]
+#[derive(Reflect, Clone)]
 pub struct ChessBoard {
     pub layout: HexLayout,
-    pub pieces: HashMap<Hex, Option<ChessPiece>>,
+    /// Private so that every change goes through [`ChessBoard::set_piece`]
+    /// and keeps `hash` up to date.
+    pieces: HashMap<Square, Option<ChessPiece>>,
+    /// The square skipped by a pawn double step on the previous move.
+    en_passant: Option<Square>,
+    /// Zobrist hash of the pieces and the en passant square, updated on every
+    /// change to either.
+    hash: u64,
 }
 
+/// The hex size of boards built with [`ChessBoard::default`] and
+/// [`ChessBoard::empty`].
+const DEFAULT_HEX_SIZE: f32 = 0.98;
+
 impl Default for ChessBoard {
     fn default() -> Self {
-        Self::new(0.98)
+        Self::new(DEFAULT_HEX_SIZE)
     }
 }
 
 impl ChessBoard {
     pub fn new(hex_size: f32) -> Self {
-        let pieces: HashMap<Hex, Option<ChessPiece>> = shapes::hexagon(Hex::ZERO, GLINSKI_RADIUS)
-            .map(|hex| (hex, None))
-            .collect();
-
-        let mut s = Self {
-            layout: HexLayout::flat().with_hex_size(hex_size),
-            pieces,
-        };
-
+        let mut s = Self::empty();
+        s.layout = HexLayout::flat().with_hex_size(hex_size);
         s.glinski_start();
 
         s
     }
 
+    /// A board with no pieces on it, to set up positions square by square.
+    pub fn empty() -> Self {
+        let pieces: HashMap<Square, Option<ChessPiece>> =
+            Square::all().map(|square| (square, None)).collect();
+
+        Self {
+            layout: HexLayout::flat().with_hex_size(DEFAULT_HEX_SIZE),
+            pieces,
+            en_passant: None,
+            hash: 0,
+        }
+    }
+
     pub fn get_piece_notation(&self, notation: &str) -> Option<ChessPiece> {
-        if let Some(hex) = Self::from_glinski_notation(notation) {
-            self.get_piece(hex)
+        if let Ok(square) = notation.parse() {
+            self.get_piece(square)
         } else {
             None
         }
     }
 
     pub fn set_piece_notation(&mut self, notation: &str, piece: Option<ChessPiece>) {
-        if let Some(hex) = Self::from_glinski_notation(notation) {
-            self.set_piece(hex, piece);
+        if let Ok(square) = notation.parse() {
+            self.set_piece(square, piece);
         }
     }
 }
 
 impl ChessBoard {
-    pub fn from_glinski_notation(notation: &str) -> Option<Hex> {
-        if notation.len() < 2 {
-            return None;
-        }
-
-        let column_char = notation.chars().next().unwrap();
-        let row_part = &notation[1..];
-        let row: i32 = row_part.parse().ok()?;
-
-        let column_index = GLINSKI_COLUMNS.iter().position(|&c| c == column_char)?;
-
-        let x = column_index as i32 - GLINSKI_COLUMN_OFFSET;
-
-        let y = if column_index < GLINSKI_COLUMN_OFFSET as usize {
-            row - x - GLINSKI_ROW_OFFSET
-        } else {
-            row - GLINSKI_ROW_OFFSET
-        };
-
-        Some(Hex::new(x, y))
-    }
-
-    pub fn to_glinski_notation(hex: Hex) -> Option<String> {
-        // Center column (F) corresponds to x = 0
-        let column_index = (hex.x() + GLINSKI_COLUMN_OFFSET) as usize;
-
-        if column_index >= GLINSKI_COLUMNS.len() {
-            return None;
-        }
-        let column = GLINSKI_COLUMNS[column_index];
-
-        let row = if column_index < GLINSKI_COLUMN_OFFSET as usize {
-            hex.y() + hex.x() + GLINSKI_ROW_OFFSET
-        } else {
-            hex.y() + GLINSKI_ROW_OFFSET
-        };
-
-        if !(1..=11).contains(&row) {
-            return None;
-        }
-
-        Some(format!("{}{}", column, row))
-    }
-
     fn glinski_start(&mut self) {
         self.set_piece_notation("B1", Some(ChessPiece::pawn(false)));
         self.set_piece_notation("C2", Some(ChessPiece::pawn(false)));
@@ -140,19 +128,173 @@ impl ChessBoard {
 }
 
 impl ChessBoard {
-    pub fn get_piece(&self, hex: Hex) -> Option<ChessPiece> {
-        self.pieces.get(&hex).copied().flatten()
+    pub fn get_piece(&self, square: Square) -> Option<ChessPiece> {
+        self.pieces.get(&square).copied().flatten()
+    }
+
+    /// Every piece on the board with its square, in no particular order.
+    pub fn pieces(&self) -> impl Iterator<Item = (Square, ChessPiece)> + '_ {
+        self.pieces
+            .iter()
+            .filter_map(|(square, piece)| piece.map(|piece| (*square, piece)))
     }
 
-    pub fn set_piece(&mut self, hex: Hex, piece: Option<ChessPiece>) {
-        if let Some(maybe_piece) = self.pieces.get_mut(&hex) {
+    /// The square skipped by a pawn double step on the previous move.
+    pub fn en_passant(&self) -> Option<Square> {
+        self.en_passant
+    }
+
+    pub fn set_piece(&mut self, square: Square, piece: Option<ChessPiece>) {
+        if let Some(maybe_piece) = self.pieces.get_mut(&square) {
+            if let Some(old) = *maybe_piece {
+                self.hash ^= piece_key(square, old);
+            }
+            if let Some(new) = piece {
+                self.hash ^= piece_key(square, new);
+            }
             *maybe_piece = piece;
         }
     }
 
-    pub fn move_piece(&mut self, from: Hex, to: Hex) {
+    pub fn set_en_passant(&mut self, en_passant: Option<Square>) {
+        if let Some(old) = self.en_passant {
+            self.hash ^= en_passant_key(old);
+        }
+        if let Some(new) = en_passant {
+            self.hash ^= en_passant_key(new);
+        }
+        self.en_passant = en_passant;
+    }
+
+    /// A number identifying the position with the given side to move. Equal
+    /// positions always hash the same, different ones almost never do.
+    pub fn hash(&self, black: bool) -> u64 {
+        if black {
+            self.hash ^ BLACK_TO_MOVE_KEY
+        } else {
+            self.hash
+        }
+    }
+
+    pub fn move_piece(&mut self, from: Square, to: Square) {
         let piece = self.get_piece(from);
         self.set_piece(to, piece);
         self.set_piece(from, None);
     }
+
+    /// Plays a move without checking it, returning what is needed to take it
+    /// back with [`ChessBoard::unapply_move`].
+    pub fn apply_move(&mut self, chess_move: &ChessMove) -> Option<MoveUndo> {
+        let from = chess_move.from;
+        let to = chess_move.to;
+        let piece = self.get_piece(from)?;
+
+        let mut undo = MoveUndo {
+            from,
+            to,
+            piece,
+            captured: self.get_piece(to),
+            captured_square: to,
+            en_passant: self.en_passant,
+        };
+
+        if self.is_en_passant(from, to) {
+            if let Some(victim) = Self::en_passant_victim(to, piece.is_black()) {
+                undo.captured_square = victim;
+                undo.captured = self.get_piece(victim);
+                self.set_piece(victim, None);
+            }
+        }
+
+        let mut en_passant = None;
+        if piece.piece_type() == PieceType::Pawn {
+            let direction = Self::pawn_direction(piece.is_black());
+            if to.hex() == from.hex() + direction * 2 {
+                en_passant = from.offset(direction);
+            }
+        }
+        self.set_en_passant(en_passant);
+
+        self.move_piece(from, to);
+
+        if let Some(promotion) = chess_move.promotion {
+            if piece.piece_type() == PieceType::Pawn
+                && Self::is_promotion_square(to, piece.is_black())
+            {
+                self.set_piece(to, Some(piece.with_type(promotion)));
+            }
+        }
+
+        Some(undo)
+    }
+
+    /// Restores the board to exactly how it was before the move described by
+    /// `undo` was applied.
+    pub fn unapply_move(&mut self, undo: &MoveUndo) {
+        self.set_piece(undo.to, None);
+        self.set_piece(undo.from, Some(undo.piece));
+        if let Some(captured) = undo.captured {
+            self.set_piece(undo.captured_square, Some(captured));
+        }
+        self.set_en_passant(undo.en_passant);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::Game;
+
+    #[test]
+    fn hash_follows_moves_and_take_backs() {
+        let mut rng = fastrand::Rng::with_seed(24);
+        let mut game = Game::default();
+        let start = game.hash();
+
+        for _ in 0..120 {
+            let moves = game.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            let chess_move = moves[rng.usize(..moves.len())];
+
+            let before = game.hash();
+            let mut board = game.board.clone();
+            let undo = board.apply_move(&chess_move).unwrap();
+            board.unapply_move(&undo);
+            assert_eq!(board.hash(game.turn.is_black()), before);
+
+            game.play(chess_move).unwrap();
+            // A board built from scratch for the same position hashes the same.
+            let fen = game.to_fen();
+            assert_eq!(Game::from_fen(&fen).unwrap().hash(), game.hash(), "{fen}");
+        }
+
+        while game.undo().is_some() {}
+        assert_eq!(game.hash(), start);
+    }
+
+    #[test]
+    fn hash_depends_on_the_position_only() {
+        let mut knights_first = Game::default();
+        let mut pawns_first = Game::default();
+        for san in ["Nc3", "Nc6", "f6", "b6"] {
+            knights_first.play_san(san).unwrap();
+        }
+        for san in ["f6", "b6", "Nc3", "Nc6"] {
+            pawns_first.play_san(san).unwrap();
+        }
+        assert_eq!(knights_first.hash(), pawns_first.hash());
+
+        let game = Game::default();
+        assert_ne!(game.board.hash(false), game.board.hash(true));
+    }
+
+    #[test]
+    fn en_passant_square_changes_the_hash() {
+        let mut double_step = Game::default();
+        double_step.play_san("e6").unwrap();
+        let mut board = double_step.board.clone();
+        board.set_en_passant(None);
+        assert_ne!(board.hash(true), double_step.hash());
+    }
 }
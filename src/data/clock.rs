@@ -0,0 +1,261 @@
+use std::{fmt, time::Duration};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::Turn;
+
+/// How much time each side gets and how it is topped up after a move.
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeControl {
+    /// The whole game must be played within `base`.
+    SuddenDeath { base: Duration },
+    /// `increment` is added after every move.
+    Fischer { base: Duration, increment: Duration },
+    /// Time used on a move is given back after it, up to `delay`.
+    Bronstein { base: Duration, delay: Duration },
+    /// `base` is added again every time `moves` more moves have been played.
+    MovesInPeriod { base: Duration, moves: u32 },
+}
+
+impl TimeControl {
+    pub fn base(self) -> Duration {
+        match self {
+            TimeControl::SuddenDeath { base }
+            | TimeControl::Fischer { base, .. }
+            | TimeControl::Bronstein { base, .. }
+            | TimeControl::MovesInPeriod { base, .. } => base,
+        }
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let minutes = |duration: Duration| duration.as_secs() / 60;
+        match *self {
+            TimeControl::SuddenDeath { base } => write!(f, "{} min", minutes(base)),
+            TimeControl::Fischer { base, increment } => {
+                write!(f, "{}+{}", minutes(base), increment.as_secs())
+            }
+            TimeControl::Bronstein { base, delay } => {
+                write!(f, "{} min, {}s delay", minutes(base), delay.as_secs())
+            }
+            TimeControl::MovesInPeriod { base, moves } => {
+                write!(f, "{moves} moves in {} min", minutes(base))
+            }
+        }
+    }
+}
+
+/// The time left to both sides, counting down for the side to move.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChessClock {
+    pub control: TimeControl,
+    /// The time left to White and to Black.
+    remaining: [Duration; 2],
+    /// Moves each side has made since the clock started.
+    moves: [u32; 2],
+    /// The side whose time is running, `None` while stopped.
+    running: Option<Turn>,
+    /// Time used by the running side on the current move.
+    move_time: Duration,
+}
+
+impl ChessClock {
+    /// A stopped clock with the full base time on both sides.
+    pub fn new(control: TimeControl) -> Self {
+        Self {
+            control,
+            remaining: [control.base(); 2],
+            moves: [0; 2],
+            running: None,
+            move_time: Duration::ZERO,
+        }
+    }
+
+    pub fn remaining(&self, side: Turn) -> Duration {
+        self.remaining[index(side)]
+    }
+
+    pub fn running(&self) -> Option<Turn> {
+        self.running
+    }
+
+    /// Runs `side`'s time from now on, as if a new move had started.
+    pub fn start(&mut self, side: Turn) {
+        self.running = Some(side);
+        self.move_time = Duration::ZERO;
+    }
+
+    pub fn stop(&mut self) {
+        self.running = None;
+    }
+
+    /// Counts `delta` off the running side's time.
+    pub fn tick(&mut self, delta: Duration) {
+        let Some(side) = self.running else {
+            return;
+        };
+        let remaining = &mut self.remaining[index(side)];
+        *remaining = remaining.saturating_sub(delta);
+        self.move_time += delta;
+    }
+
+    /// Ends `side`'s move: adds the time the control gives back and starts
+    /// the opponent's time. Returns the time added, for [`ChessClock::unpress`],
+    /// or `None` when a flag has already fallen and the move was not counted.
+    pub fn press(&mut self, side: Turn) -> Option<Duration> {
+        if self.flagged().is_some() {
+            return None;
+        }
+
+        let i = index(side);
+        self.moves[i] += 1;
+        let added = match self.control {
+            TimeControl::SuddenDeath { .. } => Duration::ZERO,
+            TimeControl::Fischer { increment, .. } => increment,
+            TimeControl::Bronstein { delay, .. } => self.move_time.min(delay),
+            TimeControl::MovesInPeriod { base, moves } => {
+                if self.moves[i].is_multiple_of(moves) {
+                    base
+                } else {
+                    Duration::ZERO
+                }
+            }
+        };
+        self.remaining[i] += added;
+
+        self.start(side.opponent());
+        Some(added)
+    }
+
+    /// Takes back `side`'s last move: it no longer counts towards a period,
+    /// the `added` time its press gave is removed again, and `side`'s time
+    /// runs once more.
+    pub fn unpress(&mut self, side: Turn, added: Duration) {
+        let i = index(side);
+        self.moves[i] = self.moves[i].saturating_sub(1);
+        self.remaining[i] = self.remaining[i].saturating_sub(added);
+        self.start(side);
+    }
+
+    /// The side that has run out of time, if any.
+    pub fn flagged(&self) -> Option<Turn> {
+        [Turn::White, Turn::Black]
+            .into_iter()
+            .find(|side| self.remaining(*side).is_zero())
+    }
+}
+
+/// Clock time as `m:ss`, with tenths of a second in the last ten seconds.
+pub fn format_clock(time: Duration) -> String {
+    let seconds = time.as_secs();
+    if seconds < 10 {
+        format!("0:0{}.{}", seconds, time.subsec_millis() / 100)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+fn index(side: Turn) -> usize {
+    if side.is_black() {
+        1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(seconds: u64) -> Duration {
+        Duration::from_secs(seconds)
+    }
+
+    /// Plays White's first move after thinking for `think`, returning the
+    /// time the press gave back.
+    fn first_move(control: TimeControl, think: Duration) -> (ChessClock, Option<Duration>) {
+        let mut clock = ChessClock::new(control);
+        clock.start(Turn::White);
+        clock.tick(think);
+        let added = clock.press(Turn::White);
+        (clock, added)
+    }
+
+    #[test]
+    fn sudden_death_adds_nothing() {
+        let (clock, added) = first_move(TimeControl::SuddenDeath { base: secs(60) }, secs(5));
+        assert_eq!(added, Some(Duration::ZERO));
+        assert_eq!(clock.remaining(Turn::White), secs(55));
+        assert_eq!(clock.running(), Some(Turn::Black));
+    }
+
+    #[test]
+    fn fischer_adds_the_increment() {
+        let control = TimeControl::Fischer {
+            base: secs(60),
+            increment: secs(2),
+        };
+        let (clock, added) = first_move(control, secs(5));
+        assert_eq!(added, Some(secs(2)));
+        assert_eq!(clock.remaining(Turn::White), secs(57));
+    }
+
+    #[test]
+    fn bronstein_gives_back_up_to_the_delay() {
+        let control = TimeControl::Bronstein {
+            base: secs(60),
+            delay: secs(3),
+        };
+        let (clock, _) = first_move(control, secs(2));
+        assert_eq!(clock.remaining(Turn::White), secs(60));
+        let (clock, _) = first_move(control, secs(5));
+        assert_eq!(clock.remaining(Turn::White), secs(58));
+    }
+
+    #[test]
+    fn moves_in_period_adds_the_base_after_each_period() {
+        let control = TimeControl::MovesInPeriod {
+            base: secs(60),
+            moves: 2,
+        };
+        let (mut clock, added) = first_move(control, Duration::ZERO);
+        assert_eq!(added, Some(Duration::ZERO));
+        clock.press(Turn::Black);
+        assert_eq!(clock.press(Turn::White), Some(secs(60)));
+        assert_eq!(clock.remaining(Turn::White), secs(120));
+    }
+
+    #[test]
+    fn unpress_takes_the_move_back() {
+        let control = TimeControl::MovesInPeriod {
+            base: secs(60),
+            moves: 2,
+        };
+        let (mut clock, _) = first_move(control, Duration::ZERO);
+        clock.press(Turn::Black);
+        let added = clock.press(Turn::White).unwrap();
+        clock.unpress(Turn::White, added);
+        assert_eq!(clock.remaining(Turn::White), secs(60));
+        assert_eq!(clock.running(), Some(Turn::White));
+
+        // The move counts again when it is played again, and only once.
+        assert_eq!(clock.press(Turn::White), Some(secs(60)));
+        assert_eq!(clock.remaining(Turn::White), secs(120));
+    }
+
+    #[test]
+    fn a_flagged_clock_stops_counting() {
+        let (mut clock, _) = first_move(TimeControl::SuddenDeath { base: secs(1) }, Duration::ZERO);
+        clock.tick(secs(2));
+        assert_eq!(clock.flagged(), Some(Turn::Black));
+        assert_eq!(clock.press(Turn::Black), None);
+    }
+
+    #[test]
+    fn format_clock_shows_tenths_at_the_end() {
+        assert_eq!(format_clock(secs(125)), "2:05");
+        assert_eq!(format_clock(Duration::from_millis(9_450)), "0:09.4");
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::{ChessMove, SearchLimits, SearchResult};
+
+/// How well the computer plays.
+#[derive(Serialize, Deserialize, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Beginner,
+    Casual,
+    #[default]
+    Intermediate,
+    Advanced,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Beginner,
+        Difficulty::Casual,
+        Difficulty::Intermediate,
+        Difficulty::Advanced,
+    ];
+
+    /// How deep and how long the computer searches. The margin is how far
+    /// below the best move, in hundredths of a pawn, a move may score and still
+    /// be picked.
+    pub fn limits(self) -> SearchLimits {
+        let (depth, nodes, margin) = match self {
+            Difficulty::Beginner => (1, 1_000, 150),
+            Difficulty::Casual => (2, 10_000, 80),
+            Difficulty::Intermediate => (3, 100_000, 30),
+            Difficulty::Advanced => (5, 300_000, 0),
+        };
+        SearchLimits {
+            depth,
+            nodes: Some(nodes),
+            margin,
+        }
+    }
+
+    /// The chance of playing any legal move instead of a searched one.
+    pub fn blunder_rate(self) -> f32 {
+        match self {
+            Difficulty::Beginner => 0.2,
+            Difficulty::Casual => 0.08,
+            Difficulty::Intermediate => 0.02,
+            Difficulty::Advanced => 0.0,
+        }
+    }
+
+    /// Picks the move to play from a search run with `self.limits()`: at
+    /// random among the moves within the margin of the best one, or now and
+    /// then among every legal move. Moves outside the margin only have a
+    /// bound below it, so they are never counted as near the best.
+    pub fn choose_move(self, result: &SearchResult, rng: &mut fastrand::Rng) -> Option<ChessMove> {
+        let moves = &result.root_moves;
+        if moves.is_empty() {
+            return result.best_move;
+        }
+
+        if rng.f32() < self.blunder_rate() {
+            return Some(moves[rng.usize(..moves.len())].0);
+        }
+
+        let floor = result.score.saturating_sub(self.limits().margin);
+        let near_best = moves.iter().filter(|(_, score)| *score >= floor).count();
+        Some(moves[rng.usize(..near_best.max(1))].0)
+    }
+
+    /// The next level, wrapping around to the easiest.
+    pub fn next(self) -> Self {
+        let i = Self::ALL
+            .iter()
+            .position(|level| *level == self)
+            .unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Beginner => "Beginner",
+            Difficulty::Casual => "Casual",
+            Difficulty::Intermediate => "Intermediate",
+            Difficulty::Advanced => "Advanced",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::ChessPiece,
+        data::{search, ChessBoard, Game, TranspositionTable},
+    };
+
+    /// White to move with the black queen hanging to the rook.
+    fn hanging_queen() -> Game {
+        let mut board = ChessBoard::empty();
+        board.set_piece_notation("B1", Some(ChessPiece::king(false)));
+        board.set_piece_notation("K1", Some(ChessPiece::king(true)));
+        board.set_piece_notation("F2", Some(ChessPiece::rook(false)));
+        board.set_piece_notation("F8", Some(ChessPiece::queen(true)));
+        Game::new(board)
+    }
+
+    #[test]
+    fn advanced_always_takes_the_queen() {
+        let game = hanging_queen();
+        let level = Difficulty::Advanced;
+        let result = search(&game, level.limits(), Some(&TranspositionTable::default()));
+        let capture = game.parse_san("Rxf8").unwrap();
+        assert_eq!(result.best_move, Some(capture));
+
+        let mut rng = fastrand::Rng::with_seed(22);
+        for _ in 0..100 {
+            assert_eq!(level.choose_move(&result, &mut rng), Some(capture));
+        }
+    }
+
+    #[test]
+    fn beginner_stays_within_its_margin() {
+        let game = hanging_queen();
+        let level = Difficulty::Beginner;
+        let result = search(&game, level.limits(), Some(&TranspositionTable::default()));
+        let floor = result.score - level.limits().margin;
+
+        let mut rng = fastrand::Rng::with_seed(22);
+        let mut near_best = 0;
+        for seed in 0..200 {
+            // The same draw `choose_move` makes to decide on a blunder.
+            let blunders = fastrand::Rng::with_seed(seed).f32() < level.blunder_rate();
+            rng.seed(seed);
+            let chosen = level.choose_move(&result, &mut rng).unwrap();
+            if blunders {
+                continue;
+            }
+            let (_, score) = result
+                .root_moves
+                .iter()
+                .find(|(chess_move, _)| *chess_move == chosen)
+                .unwrap();
+            assert!(*score >= floor, "{chosen:?} scores {score}, under {floor}");
+            near_best += 1;
+        }
+        assert!(near_best > 100);
+    }
+
+    #[test]
+    fn moves_failing_low_are_not_near_the_best() {
+        let positions: [(Difficulty, &[&str]); 2] = [
+            (
+                Difficulty::Casual,
+                &[
+                    "Qe3", "b6", "Ne1", "k5", "Nh1", "Rif8", "g6", "d5", "h4", "Ng9", "h5", "Bd7",
+                    "Bg2", "Bb7", "b2", "d4", "Qe2", "Nd8", "Bh2", "Ri8", "Bh3", "Ni6", "Ng2",
+                ],
+            ),
+            (
+                Difficulty::Intermediate,
+                &[
+                    "Be3", "Be8", "Bf2", "Qg9", "h4", "Bf10", "d5", "Nb6", "c4", "Rcf8", "Ne2",
+                    "Rfh8", "Qb4", "Nd9", "h5", "Be10", "k2", "c6", "Kh1", "Qf9",
+                ],
+            ),
+        ];
+
+        for (level, sans) in positions {
+            let mut game = Game::default();
+            for san in sans {
+                game.play_san(san).unwrap();
+            }
+            let limits = SearchLimits {
+                nodes: None,
+                ..level.limits()
+            };
+            let result = search(&game, limits, Some(&TranspositionTable::default()));
+            let floor = result.score - limits.margin;
+
+            // Every move counted as near the best must really be, as found by
+            // searching it again with an open window.
+            for (chess_move, score) in result.root_moves.iter() {
+                if *score < floor {
+                    break;
+                }
+                let mut after = game.clone();
+                after.play(*chess_move).unwrap();
+                let exact_limits = SearchLimits {
+                    depth: result.depth - 1,
+                    nodes: None,
+                    margin: 0,
+                };
+                let exact = -search(&after, exact_limits, None).score;
+                assert!(
+                    exact >= floor,
+                    "{level} {chess_move:?} scored {score} but is {exact}, under {floor}"
+                );
+            }
+        }
+    }
+}
@@ -1,17 +1,374 @@
-use crate::components::PieceType;
+use std::time::Duration;
 
+use anyhow::{bail, Result};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{ChessPiece, PieceType},
+    data::{ChessBoard, ChessClock, MoveUndo, Square},
+};
+
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ChessMove {
-    pub from: String,
-    pub to: String,
+    pub from: Square,
+    pub to: Square,
     pub promotion: Option<PieceType>,
 }
 
+impl ChessMove {
+    pub fn new(from: Square, to: Square) -> Self {
+        Self {
+            from,
+            to,
+            promotion: None,
+        }
+    }
+
+    pub fn with_promotion(mut self, promotion: PieceType) -> Self {
+        self.promotion = Some(promotion);
+        self
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Turn {
     White,
     Black,
 }
 
+impl Turn {
+    pub fn from_black(black: bool) -> Self {
+        if black {
+            Turn::Black
+        } else {
+            Turn::White
+        }
+    }
+    pub fn is_black(self) -> bool {
+        matches!(self, Turn::Black)
+    }
+    pub fn opponent(self) -> Self {
+        match self {
+            Turn::White => Turn::Black,
+            Turn::Black => Turn::White,
+        }
+    }
+}
+
+/// How a finished game ended. Glinski rules do not treat stalemate as a draw:
+/// the side delivering it scores 3/4 and the stalemated side 1/4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    Checkmate {
+        winner: Turn,
+    },
+    Stalemate {
+        winner: Turn,
+    },
+    /// The loser's clock ran out while the winner could still mate.
+    Timeout {
+        winner: Turn,
+    },
+    Draw,
+}
+
+impl GameResult {
+    /// The points scored by `side`.
+    pub fn score(self, side: Turn) -> f32 {
+        match self {
+            GameResult::Checkmate { winner } | GameResult::Timeout { winner } if winner == side => {
+                1.0
+            }
+            GameResult::Checkmate { .. } | GameResult::Timeout { .. } => 0.0,
+            GameResult::Stalemate { winner } if winner == side => 0.75,
+            GameResult::Stalemate { .. } => 0.25,
+            GameResult::Draw => 0.5,
+        }
+    }
+}
+
+/// The authoritative state of a game: the board, whose turn it is and every
+/// move played so far, which can be taken back one at a time.
+#[derive(Reflect, Clone)]
 pub struct Game {
+    pub board: ChessBoard,
     pub turn: Turn,
     pub history: Vec<ChessMove>,
-}
\ No newline at end of file
+    /// `history` in Glinski algebraic notation, written as each move is
+    /// played so the move list never has to replay the game.
+    sans: Vec<String>,
+    /// The side to move and the fullmove number before the first move in
+    /// `history`.
+    pub start_turn: Turn,
+    pub start_fullmove_number: u32,
+    /// Every piece captured so far, in the order they were taken.
+    pub captured: Vec<ChessPiece>,
+    /// Moves since the last capture or pawn move.
+    pub halfmove_clock: u32,
+    /// Starts at 1 and goes up after every Black move.
+    pub fullmove_number: u32,
+    /// The players' clocks, `None` for games without a time limit.
+    pub clock: Option<ChessClock>,
+    /// Each move's undo paired with the halfmove clock from before it and the
+    /// time the chess clock added for it.
+    undo_stack: Vec<(MoveUndo, u32, Option<Duration>)>,
+    /// Moves taken back with [`Game::take_back`], the next one to redo last.
+    redo_stack: Vec<ChessMove>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new(ChessBoard::default())
+    }
+}
+
+impl Game {
+    pub fn new(board: ChessBoard) -> Self {
+        Self {
+            board,
+            turn: Turn::White,
+            history: Vec::new(),
+            sans: Vec::new(),
+            start_turn: Turn::White,
+            start_fullmove_number: 1,
+            captured: Vec::new(),
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            clock: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+
+    pub fn legal_moves(&self) -> Vec<ChessMove> {
+        self.board.legal_moves(self.turn.is_black())
+    }
+
+    pub fn is_in_check(&self) -> bool {
+        self.board.is_in_check(self.turn.is_black())
+    }
+
+    /// Identifies the position and the side to move, see [`ChessBoard::hash`].
+    pub fn hash(&self) -> u64 {
+        self.board.hash(self.turn.is_black())
+    }
+
+    /// How the game ended, by mate, stalemate or a flag falling.
+    pub fn result(&self) -> Option<GameResult> {
+        self.position_result().or_else(|| self.time_result())
+    }
+
+    /// Mate or stalemate of the side to move. Generates every legal move, so
+    /// callers running each frame should keep it until the position changes.
+    pub fn position_result(&self) -> Option<GameResult> {
+        self.board.game_result(self.turn.is_black())
+    }
+
+    /// The result of a flag falling, cheap enough to check every frame.
+    pub fn time_result(&self) -> Option<GameResult> {
+        let flagged = self.clock.as_ref().and_then(ChessClock::flagged)?;
+        let winner = flagged.opponent();
+        if self.board.has_mating_material(winner.is_black()) {
+            Some(GameResult::Timeout { winner })
+        } else {
+            Some(GameResult::Draw)
+        }
+    }
+
+    /// Counts `delta` off the clock of the side to move.
+    pub fn tick(&mut self, delta: Duration) {
+        if let Some(clock) = &mut self.clock {
+            clock.tick(delta);
+        }
+    }
+
+    pub fn last_move(&self) -> Option<&MoveUndo> {
+        self.undo_stack.last().map(|(undo, _, _)| undo)
+    }
+
+    /// A copy of the game as it was after its first `ply` moves.
+    pub fn at_ply(&self, ply: usize) -> Self {
+        let mut game = self.clone();
+        while game.history.len() > ply && game.undo().is_some() {}
+        game
+    }
+
+    /// Validates and plays a move for the side to move. Playing a move drops
+    /// any moves that could have been redone.
+    pub fn play(&mut self, chess_move: ChessMove) -> Result<()> {
+        let Some(piece) = self.board.get_piece(chess_move.from) else {
+            bail!("there is no piece on {}", chess_move.from);
+        };
+        if piece.is_black() != self.turn.is_black() {
+            bail!("it is not {:?}'s turn", Turn::from_black(piece.is_black()));
+        }
+        if !self.board.is_legal(&chess_move) {
+            bail!("{}-{} is not a legal move", chess_move.from, chess_move.to);
+        }
+
+        let san = self.board.to_san(&chess_move);
+        let Some(undo) = self.board.apply_move(&chess_move) else {
+            bail!("{}-{} could not be applied", chess_move.from, chess_move.to);
+        };
+
+        if let Some(captured) = undo.captured {
+            self.captured.push(captured);
+        }
+        let halfmove_clock = self.halfmove_clock;
+        self.history.push(chess_move);
+        self.sans.push(san);
+
+        if undo.captured.is_some() || piece.piece_type() == PieceType::Pawn {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if self.turn.is_black() {
+            self.fullmove_number += 1;
+        }
+        let added = self.clock.as_mut().and_then(|clock| clock.press(self.turn));
+        self.undo_stack.push((undo, halfmove_clock, added));
+        self.turn = self.turn.opponent();
+        self.redo_stack.clear();
+
+        Ok(())
+    }
+
+    /// Takes back the last move, returning it.
+    pub fn undo(&mut self) -> Option<ChessMove> {
+        let (undo, halfmove_clock, added) = self.undo_stack.pop()?;
+        let chess_move = self.history.pop();
+        self.sans.pop();
+
+        self.board.unapply_move(&undo);
+        if undo.captured.is_some() {
+            self.captured.pop();
+        }
+        self.halfmove_clock = halfmove_clock;
+        self.turn = self.turn.opponent();
+        if let Some(clock) = &mut self.clock {
+            match added {
+                Some(added) => clock.unpress(self.turn, added),
+                None => clock.start(self.turn),
+            }
+        }
+        if self.turn.is_black() {
+            self.fullmove_number -= 1;
+        }
+
+        chess_move
+    }
+
+    /// Undoes the last move, keeping it so that it can be redone.
+    pub fn take_back(&mut self) -> Option<ChessMove> {
+        let chess_move = self.undo()?;
+        self.redo_stack.push(chess_move);
+        Some(chess_move)
+    }
+
+    /// Plays again the last move taken back, returning it.
+    pub fn redo(&mut self) -> Result<Option<ChessMove>> {
+        let Some(chess_move) = self.redo_stack.pop() else {
+            return Ok(None);
+        };
+
+        let redo_stack = std::mem::take(&mut self.redo_stack);
+        let played = self.play(chess_move);
+        self.redo_stack = redo_stack;
+        played?;
+
+        Ok(Some(chess_move))
+    }
+
+    /// The moves played so far, written in Glinski algebraic notation.
+    pub fn history_san(&self) -> &[String] {
+        &self.sans
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::TimeControl;
+
+    /// Everything `play` changes and `undo` has to put back.
+    fn snapshot(game: &Game) -> (String, Vec<ChessPiece>, Vec<String>, u64) {
+        (
+            game.to_fen(),
+            game.captured.clone(),
+            game.history_san().to_vec(),
+            game.hash(),
+        )
+    }
+
+    #[test]
+    fn undo_restores_every_counter() {
+        let mut board = ChessBoard::empty();
+        board.set_piece_notation("B1", Some(ChessPiece::king(false)));
+        board.set_piece_notation("K1", Some(ChessPiece::king(true)));
+        board.set_piece_notation("E4", Some(ChessPiece::pawn(false)));
+        board.set_piece_notation("F10", Some(ChessPiece::pawn(false)));
+        board.set_piece_notation("E10", Some(ChessPiece::rook(true)));
+        let mut game = Game::new(board);
+
+        let mut snapshots = Vec::new();
+        for san in ["Kb2", "Kk2", "e6", "Kk3", "fxe10=Q"] {
+            snapshots.push(snapshot(&game));
+            game.play_san(san).unwrap();
+        }
+        assert_eq!(game.captured, vec![ChessPiece::rook(true)]);
+        assert_eq!(game.halfmove_clock, 0);
+        assert_eq!(game.fullmove_number, 3);
+        assert_eq!(game.turn, Turn::Black);
+
+        let end = snapshot(&game);
+        while let Some(expected) = snapshots.pop() {
+            game.take_back().unwrap();
+            assert_eq!(snapshot(&game), expected);
+        }
+        assert_eq!(game.turn, Turn::White);
+        assert_eq!(game.fullmove_number, 1);
+        assert_eq!(game.halfmove_clock, 0);
+        assert!(game.undo().is_none());
+
+        // An illegal move changes nothing, not even the moves to redo.
+        let before = snapshot(&game);
+        let illegal = ChessMove::new("E4".parse().unwrap(), "E7".parse().unwrap());
+        assert!(game.play(illegal).is_err());
+        assert!(game.play_san("Kk2").is_err());
+        assert_eq!(snapshot(&game), before);
+        assert!(game.can_redo());
+
+        while game.redo().unwrap().is_some() {}
+        assert_eq!(snapshot(&game), end);
+        assert!(!game.can_redo());
+    }
+
+    #[test]
+    fn undo_and_redo_do_not_add_clock_time_twice() {
+        let mut game = Game::default();
+        let mut clock = ChessClock::new(TimeControl::MovesInPeriod {
+            base: Duration::from_secs(60),
+            moves: 1,
+        });
+        clock.start(Turn::White);
+        game.clock = Some(clock);
+
+        game.play_san("f6").unwrap();
+        let after_move = game.clock.unwrap().remaining(Turn::White);
+        assert_eq!(after_move, Duration::from_secs(120));
+
+        game.take_back();
+        let clock = game.clock.unwrap();
+        assert_eq!(clock.remaining(Turn::White), Duration::from_secs(60));
+        assert_eq!(clock.running(), Some(Turn::White));
+
+        game.redo().unwrap();
+        assert_eq!(game.clock.unwrap().remaining(Turn::White), after_move);
+    }
+}
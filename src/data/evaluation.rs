@@ -0,0 +1,396 @@
+use std::sync::LazyLock;
+
+use hexx::Hex;
+
+use crate::{
+    components::{ChessPiece, PieceType},
+    data::{
+        ChessBoard, Square, DIAGONAL_DIRECTIONS, GLINSKI_RADIUS, KNIGHT_OFFSETS,
+        ORTHOGONAL_DIRECTIONS, SQUARE_COUNT,
+    },
+};
+
+/// Knights, bishops, rooks and queens of both sides at the start. The score
+/// moves from the opening terms to the endgame ones as this material goes.
+const OPENING_MATERIAL: i32 = 2 * (2 * 300 + 3 * 350 + 2 * 500 + 900);
+
+/// Every pawn starts this many steps away from promotion, on every file.
+const PAWN_START_DISTANCE: i32 = 6;
+
+const DOUBLED_PAWN: i32 = 15;
+const ISOLATED_PAWN: i32 = 12;
+/// For each own pawn standing just in front of the king.
+const PAWN_SHIELD: i32 = 12;
+/// For each square sharing an edge with the king that the opponent attacks.
+const KING_ZONE_ATTACK: i32 = 10;
+/// By the number of cell colours a side still has bishops on.
+const BISHOP_COVERAGE: [i32; 4] = [0, 0, 15, 40];
+
+const QUEEN_DIRECTIONS: [Hex; 12] = {
+    let mut directions = [Hex::ZERO; 12];
+    let mut i = 0;
+    while i < 6 {
+        directions[i] = ORTHOGONAL_DIRECTIONS[i];
+        directions[i + 6] = DIAGONAL_DIRECTIONS[i];
+        i += 1;
+    }
+    directions
+};
+
+static TABLES: LazyLock<PieceSquareTables> = LazyLock::new(PieceSquareTables::new);
+
+/// Bonuses for standing on each of the 91 squares, from White's side of the
+/// board. Black looks them up on the mirrored square.
+struct PieceSquareTables {
+    opening: [[i32; SQUARE_COUNT]; 6],
+    endgame: [[i32; SQUARE_COUNT]; 6],
+}
+
+impl PieceSquareTables {
+    fn new() -> Self {
+        let mut opening = [[0; SQUARE_COUNT]; 6];
+        let mut endgame = [[0; SQUARE_COUNT]; 6];
+
+        for square in Square::all() {
+            let i = square.index();
+            // 5 on the centre cell down to 0 on the rim.
+            let centre = (GLINSKI_RADIUS - square.hex().ulength()) as i32;
+            let progress = pawn_progress(square, false);
+            let home = square.rank() - 1;
+
+            let pawn = progress * progress * 3 + centre * 3;
+            let by_piece = [
+                (PieceType::Pawn, pawn, pawn * 2),
+                (PieceType::Knight, centre * 10 - 20, centre * 8 - 15),
+                (PieceType::Bishop, centre * 6 - 10, centre * 5 - 10),
+                (PieceType::Rook, centre * 3, centre * 3),
+                (PieceType::Queen, centre * 4 - 5, centre * 6 - 10),
+                (PieceType::King, -home * 15, centre * 10 - 20),
+            ];
+            for (piece_type, opening_bonus, endgame_bonus) in by_piece {
+                opening[piece_type as usize][i] = opening_bonus;
+                endgame[piece_type as usize][i] = endgame_bonus;
+            }
+        }
+
+        Self { opening, endgame }
+    }
+
+    fn get(&self, piece: ChessPiece, square: Square) -> (i32, i32) {
+        let square = if piece.is_black() {
+            square.mirror()
+        } else {
+            square
+        };
+        let piece_type = piece.piece_type() as usize;
+        (
+            self.opening[piece_type][square.index()],
+            self.endgame[piece_type][square.index()],
+        )
+    }
+}
+
+/// A score in the opening and one in the endgame, blended by how much
+/// material is left.
+#[derive(Default, Clone, Copy)]
+struct Tapered {
+    opening: i32,
+    endgame: i32,
+}
+
+impl Tapered {
+    fn add(&mut self, opening: i32, endgame: i32) {
+        self.opening += opening;
+        self.endgame += endgame;
+    }
+
+    fn blend(self, material: i32) -> i32 {
+        let phase = material.min(OPENING_MATERIAL);
+        (self.opening * phase + self.endgame * (OPENING_MATERIAL - phase)) / OPENING_MATERIAL
+    }
+}
+
+impl ChessBoard {
+    /// A static score of the position from the point of view of the given
+    /// side, in hundredths of a pawn: positive when it is ahead.
+    pub fn evaluate(&self, black: bool) -> i32 {
+        let mut material = 0;
+        let mut sides = [Tapered::default(); 2];
+        let mut pawn_files = [[0; 11]; 2];
+        let mut bishop_shades = [[false; 3]; 2];
+        let mut kings = [None; 2];
+
+        for (square, piece) in self.pieces() {
+            let colour = piece.is_black() as usize;
+            let (opening, endgame) = TABLES.get(piece, square);
+            sides[colour].add(piece.value() + opening, piece.value() + endgame);
+
+            let weight = match piece.piece_type() {
+                PieceType::Pawn => {
+                    pawn_files[colour][square.file()] += 1;
+                    continue;
+                }
+                PieceType::King => {
+                    kings[colour] = Some(square);
+                    continue;
+                }
+                PieceType::Knight => 4,
+                PieceType::Bishop => {
+                    bishop_shades[colour][square.shade()] = true;
+                    3
+                }
+                PieceType::Rook => 2,
+                PieceType::Queen => 1,
+            };
+            material += piece.value();
+
+            let mobility = self.mobility(square, piece);
+            sides[colour].add(mobility * weight, mobility * weight);
+        }
+
+        for is_black in [false, true] {
+            let side = &mut sides[is_black as usize];
+            let own_files = &pawn_files[is_black as usize];
+
+            for (square, piece) in self.pieces() {
+                if piece != ChessPiece::pawn(is_black) {
+                    continue;
+                }
+                let (opening, endgame) = self.pawn_structure(square, is_black, own_files);
+                side.add(opening, endgame);
+            }
+
+            let doubled: i32 = own_files.iter().map(|&count| (count - 1).max(0)).sum();
+            side.add(-doubled * DOUBLED_PAWN, -doubled * DOUBLED_PAWN);
+
+            let shades = bishop_shades[is_black as usize];
+            let coverage = BISHOP_COVERAGE[shades.iter().filter(|&&shade| shade).count()];
+            side.add(coverage, coverage);
+
+            if let Some(king) = kings[is_black as usize] {
+                side.add(self.king_safety(king, is_black), 0);
+            }
+        }
+
+        let white = sides[0].blend(material);
+        let black_score = sides[1].blend(material);
+        if black {
+            black_score - white
+        } else {
+            white - black_score
+        }
+    }
+
+    /// The number of squares the piece on `square` can move to, counted
+    /// without building the moves.
+    fn mobility(&self, square: Square, piece: ChessPiece) -> i32 {
+        let reachable = |to: Square| {
+            self.get_piece(to)
+                .is_none_or(|other| piece.is_opponent(other))
+        };
+
+        let directions: &[Hex] = match piece.piece_type() {
+            PieceType::Knight => {
+                return KNIGHT_OFFSETS
+                    .iter()
+                    .filter_map(|offset| square.offset(*offset))
+                    .filter(|to| reachable(*to))
+                    .count() as i32;
+            }
+            PieceType::Bishop => &DIAGONAL_DIRECTIONS,
+            PieceType::Rook => &ORTHOGONAL_DIRECTIONS,
+            PieceType::Queen => &QUEEN_DIRECTIONS,
+            PieceType::Pawn | PieceType::King => return 0,
+        };
+
+        let mut count = 0;
+        for direction in directions {
+            let mut next = square.offset(*direction);
+            while let Some(to) = next {
+                if reachable(to) {
+                    count += 1;
+                }
+                if self.get_piece(to).is_some() {
+                    break;
+                }
+                next = to.offset(*direction);
+            }
+        }
+
+        count
+    }
+
+    /// Penalties for an isolated pawn and bonuses for a passed one, which
+    /// grow as it nears promotion and matter most in the endgame.
+    fn pawn_structure(&self, square: Square, black: bool, own_files: &[i32; 11]) -> (i32, i32) {
+        let file = square.file();
+        let neighbours = [file.checked_sub(1), Some(file + 1)];
+        let isolated = neighbours
+            .into_iter()
+            .flatten()
+            .all(|file| own_files.get(file).is_none_or(|&count| count == 0));
+
+        let (mut opening, mut endgame) = (0, 0);
+        if isolated {
+            opening -= ISOLATED_PAWN;
+            endgame -= ISOLATED_PAWN;
+        }
+        if self.is_passed_pawn(square, black) {
+            let progress = pawn_progress(square, black);
+            opening += 10 + progress * 5;
+            endgame += 20 + progress * progress * 6;
+        }
+
+        (opening, endgame)
+    }
+
+    /// Whether no opposing pawn stands on, or can capture on, any square the
+    /// pawn on `square` still has to cross.
+    fn is_passed_pawn(&self, square: Square, black: bool) -> bool {
+        let enemy = ChessPiece::pawn(!black);
+        let mut next = square.offset(Self::pawn_direction(black));
+        while let Some(ahead) = next {
+            if self.get_piece(ahead) == Some(enemy) {
+                return false;
+            }
+            for direction in Self::pawn_capture_directions(!black) {
+                if ahead
+                    .offset(-direction)
+                    .is_some_and(|from| self.get_piece(from) == Some(enemy))
+                {
+                    return false;
+                }
+            }
+            next = ahead.offset(Self::pawn_direction(black));
+        }
+
+        true
+    }
+
+    /// Rewards pawns sheltering the king and punishes attacks on the squares
+    /// around it. Only counted in the opening and middlegame.
+    fn king_safety(&self, king: Square, black: bool) -> i32 {
+        let forward = Self::pawn_direction(black);
+        let [left, right] = Self::pawn_capture_directions(black);
+        let shield = [forward, left, right]
+            .into_iter()
+            .filter_map(|direction| king.offset(direction))
+            .filter(|square| self.get_piece(*square) == Some(ChessPiece::pawn(black)))
+            .count() as i32;
+
+        let attacked = ORTHOGONAL_DIRECTIONS
+            .iter()
+            .filter_map(|direction| king.offset(*direction))
+            .filter(|square| self.is_attacked(*square, !black))
+            .count() as i32;
+
+        shield * PAWN_SHIELD - attacked * KING_ZONE_ATTACK
+    }
+}
+
+/// How many steps a pawn on `square` has made towards promotion, counted
+/// from the starting distance shared by every file.
+fn pawn_progress(square: Square, black: bool) -> i32 {
+    let direction = ChessBoard::pawn_direction(black);
+    let mut remaining = 0;
+    let mut next = square.offset(direction);
+    while let Some(ahead) = next {
+        remaining += 1;
+        next = ahead.offset(direction);
+    }
+
+    (PAWN_START_DISTANCE - remaining).max(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Game;
+
+    /// The same position seen from the other side: every piece moved to its
+    /// mirrored square and given to the other colour.
+    fn mirrored(board: &ChessBoard) -> ChessBoard {
+        let mut mirror = ChessBoard::empty();
+        for (square, piece) in board.pieces() {
+            let swapped = ChessPiece::pawn(!piece.is_black()).with_type(piece.piece_type());
+            mirror.set_piece(square.mirror(), Some(swapped));
+        }
+        mirror
+    }
+
+    /// The two kings on their starting squares and nothing else.
+    fn kings_only() -> ChessBoard {
+        let mut board = ChessBoard::empty();
+        board.set_piece_notation("G1", Some(ChessPiece::king(false)));
+        board.set_piece_notation("G10", Some(ChessPiece::king(true)));
+        board
+    }
+
+    /// The two kings and white pawns on `squares`.
+    fn with_pawns(squares: &[&str]) -> ChessBoard {
+        let mut board = kings_only();
+        for square in squares {
+            board.set_piece_notation(square, Some(ChessPiece::pawn(false)));
+        }
+        board
+    }
+
+    #[test]
+    fn start_position_is_level() {
+        let board = ChessBoard::default();
+        assert_eq!(board.evaluate(false), 0);
+        assert_eq!(board.evaluate(true), 0);
+    }
+
+    #[test]
+    fn mirrored_positions_score_the_opposite() {
+        let mut rng = fastrand::Rng::with_seed(23);
+        let mut game = Game::default();
+        for _ in 0..60 {
+            let board = &game.board;
+            let score = board.evaluate(false);
+            assert_eq!(board.evaluate(true), -score);
+            assert_eq!(mirrored(board).evaluate(false), -score);
+
+            let moves = game.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            game.play(moves[rng.usize(..moves.len())]).unwrap();
+        }
+    }
+
+    #[test]
+    fn doubled_and_isolated_pawns_score_worse() {
+        let healthy = with_pawns(&["D3", "E4", "F4"]).evaluate(false);
+        let doubled = with_pawns(&["D3", "E4", "E3"]).evaluate(false);
+        assert!(doubled < healthy, "{doubled} against {healthy}");
+
+        let connected = with_pawns(&["D3", "E4", "F5"]).evaluate(false);
+        let isolated = with_pawns(&["C2", "E4", "F5"]).evaluate(false);
+        assert!(isolated < connected, "{isolated} against {connected}");
+    }
+
+    #[test]
+    fn exposed_king_scores_worse() {
+        let king = "G1".parse::<Square>().unwrap();
+        let forward = ChessBoard::pawn_direction(false);
+        let [left, right] = ChessBoard::pawn_capture_directions(false);
+        let shield: Vec<Square> = [forward, left, right]
+            .into_iter()
+            .filter_map(|direction| king.offset(direction))
+            .collect();
+
+        let mut sheltered = kings_only();
+        for square in shield.iter() {
+            sheltered.set_piece(*square, Some(ChessPiece::pawn(false)));
+        }
+        let bare = kings_only();
+        assert!(bare.king_safety(king, false) < sheltered.king_safety(king, false));
+
+        // A rook bearing down on the squares next to the king.
+        let mut attacked = bare.clone();
+        attacked.set_piece_notation("F8", Some(ChessPiece::rook(true)));
+        assert!(attacked.king_safety(king, false) < bare.king_safety(king, false));
+    }
+}
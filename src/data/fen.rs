@@ -0,0 +1,256 @@
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    components::{ChessPiece, PieceType},
+    data::{ChessBoard, Game, Square, Turn, GLINSKI_COLUMNS},
+};
+
+/// The Glinski starting position, as written by [`Game::to_fen`].
+pub const GLINSKI_START_FEN: &str =
+    "6/P5p/RP4pr/N1P3p1n/Q2P2p2q/BBB1P1p1bbb/K2P2p2k/N1P3p1n/RP4pr/P5p/6 w - 0 1";
+
+impl ChessBoard {
+    /// The piece placement part of a position string. Files are listed from
+    /// `A` to `L` separated by `/`, each from rank 1 upwards, with white
+    /// pieces in upper case, black pieces in lower case and runs of empty
+    /// squares as numbers.
+    pub fn to_fen_placement(&self) -> String {
+        let mut files = Vec::with_capacity(GLINSKI_COLUMNS.len());
+        for file in 0..GLINSKI_COLUMNS.len() {
+            let mut text = String::new();
+            let mut empty = 0;
+            for square in file_squares(file) {
+                match self.get_piece(square) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            text.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        text.push(piece_char(piece));
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                text.push_str(&empty.to_string());
+            }
+            files.push(text);
+        }
+
+        files.join("/")
+    }
+
+    /// Replaces every piece on the board with the placement read from a
+    /// position string, keeping the layout.
+    pub fn set_fen_placement(&mut self, placement: &str) -> Result<()> {
+        let files: Vec<&str> = placement.split('/').collect();
+        if files.len() != GLINSKI_COLUMNS.len() {
+            bail!(
+                "expected {} files separated by '/' but found {}",
+                GLINSKI_COLUMNS.len(),
+                files.len()
+            );
+        }
+
+        let mut pieces = Vec::new();
+        for (file, text) in files.into_iter().enumerate() {
+            let squares: Vec<Square> = file_squares(file).collect();
+            let name = GLINSKI_COLUMNS[file];
+
+            let mut rank = 0;
+            let mut chars = text.chars().peekable();
+            while let Some(c) = chars.next() {
+                if let Some(digit) = c.to_digit(10) {
+                    let mut empty = digit as usize;
+                    loop {
+                        // Checked after every digit so long runs cannot overflow.
+                        if rank + empty > squares.len() {
+                            bail!("file {name} has more than {} squares", squares.len());
+                        }
+                        let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) else {
+                            break;
+                        };
+                        empty = empty * 10 + digit as usize;
+                        chars.next();
+                    }
+                    rank += empty;
+                } else {
+                    let piece = parse_piece(c)
+                        .ok_or_else(|| anyhow!("'{c}' on file {name} is not a piece"))?;
+                    let Some(square) = squares.get(rank) else {
+                        bail!("file {name} has more than {} squares", squares.len());
+                    };
+                    pieces.push((*square, piece));
+                    rank += 1;
+                }
+            }
+
+            if rank != squares.len() {
+                bail!(
+                    "file {name} describes {rank} squares but has {}",
+                    squares.len()
+                );
+            }
+        }
+
+        for square in Square::all() {
+            self.set_piece(square, None);
+        }
+        for (square, piece) in pieces {
+            self.set_piece(square, Some(piece));
+        }
+
+        Ok(())
+    }
+}
+
+impl Game {
+    /// A position string such as [`GLINSKI_START_FEN`]: the piece placement,
+    /// the side to move, the en passant square, the halfmove clock and the
+    /// fullmove number separated by spaces.
+    pub fn to_fen(&self) -> String {
+        let turn = if self.turn.is_black() { 'b' } else { 'w' };
+        let en_passant = self
+            .board
+            .en_passant()
+            .map(|square| square.to_string().to_ascii_lowercase())
+            .unwrap_or_else(|| String::from("-"));
+
+        format!(
+            "{} {} {} {} {}",
+            self.board.to_fen_placement(),
+            turn,
+            en_passant,
+            self.halfmove_clock,
+            self.fullmove_number
+        )
+    }
+
+    /// A new game starting from the position string, with an empty history.
+    pub fn from_fen(fen: &str) -> Result<Self> {
+        let mut game = Self::default();
+        game.load_fen(fen)?;
+        Ok(game)
+    }
+
+    /// Replaces the position with the position string and clears the history,
+    /// keeping the board layout.
+    pub fn load_fen(&mut self, fen: &str) -> Result<()> {
+        let mut fields = fen.split_whitespace();
+        let mut next = |name: &str| {
+            fields
+                .next()
+                .ok_or_else(|| anyhow!("the position is missing the {name}"))
+        };
+
+        let placement = next("piece placement")?;
+        let turn = match next("side to move")? {
+            "w" => Turn::White,
+            "b" => Turn::Black,
+            other => bail!("'{other}' is not a side to move, expected 'w' or 'b'"),
+        };
+        let en_passant = match next("en passant square")? {
+            "-" => None,
+            square => Some(
+                square
+                    .parse::<Square>()
+                    .context("the en passant square is invalid")?,
+            ),
+        };
+        let halfmove_clock = next("halfmove clock")?
+            .parse()
+            .context("the halfmove clock is not a number")?;
+        let fullmove_number = next("fullmove number")?
+            .parse()
+            .context("the fullmove number is not a number")?;
+
+        let mut board = self.board.clone();
+        board.set_fen_placement(placement)?;
+        board.set_en_passant(en_passant);
+
+        *self = Self::new(board);
+        self.turn = turn;
+        self.halfmove_clock = halfmove_clock;
+        self.fullmove_number = fullmove_number;
+        self.start_turn = turn;
+        self.start_fullmove_number = fullmove_number;
+
+        Ok(())
+    }
+}
+
+fn file_squares(file: usize) -> impl Iterator<Item = Square> {
+    (1..).map_while(move |rank| Square::from_file_rank(file, rank))
+}
+
+fn piece_char(piece: ChessPiece) -> char {
+    let c = piece.piece_type().to_char();
+    if piece.is_black() {
+        c.to_ascii_lowercase()
+    } else {
+        c
+    }
+}
+
+fn parse_piece(c: char) -> Option<ChessPiece> {
+    let piece_type = PieceType::from_char(c.to_ascii_uppercase())?;
+    let piece = ChessPiece::pawn(c.is_ascii_lowercase());
+    Some(piece.with_type(piece_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_position_matches_the_constant() {
+        assert_eq!(Game::default().to_fen(), GLINSKI_START_FEN);
+        let game = Game::from_fen(GLINSKI_START_FEN).unwrap();
+        assert_eq!(game.to_fen(), GLINSKI_START_FEN);
+    }
+
+    #[test]
+    fn positions_round_trip() {
+        let mut game = Game::default();
+        for san in ["e6", "d5", "exd6", "Nc6", "Qe3"] {
+            game.play_san(san).unwrap();
+            let fen = game.to_fen();
+            let read = Game::from_fen(&fen).unwrap();
+            assert_eq!(read.to_fen(), fen);
+            assert_eq!(read.turn, game.turn);
+            assert_eq!(read.board.en_passant(), game.board.en_passant());
+            for square in Square::all() {
+                assert_eq!(read.board.get_piece(square), game.board.get_piece(square));
+            }
+        }
+    }
+
+    #[test]
+    fn en_passant_square_is_kept() {
+        let mut game = Game::default();
+        game.play_san("e6").unwrap();
+        let fen = game.to_fen();
+        assert!(fen.contains(" b e5 "), "{fen}");
+        assert_eq!(
+            Game::from_fen(&fen).unwrap().board.en_passant(),
+            Some("E5".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn bad_positions_are_rejected() {
+        let bad = [
+            String::new(),
+            String::from("6/7 w - 0 1"),
+            GLINSKI_START_FEN.replacen('6', "x", 1),
+            GLINSKI_START_FEN.replace(" w ", " x "),
+            GLINSKI_START_FEN.replace(" - ", " j5 "),
+            GLINSKI_START_FEN.replace(" 0 1", " zero 1"),
+            GLINSKI_START_FEN.replacen('6', "99999999999999999999", 1),
+            GLINSKI_START_FEN.replacen('6', "12", 1),
+        ];
+        for fen in bad {
+            assert!(Game::from_fen(&fen).is_err(), "'{fen}' was accepted");
+        }
+    }
+}
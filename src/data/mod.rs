@@ -1,5 +1,35 @@
 mod chess_board;
 pub use self::chess_board::*;
 
+mod clock;
+pub use self::clock::*;
+
+mod difficulty;
+pub use self::difficulty::*;
+
 mod engine;
 pub use self::engine::*;
+
+mod evaluation;
+
+mod fen;
+pub use self::fen::*;
+
+mod move_generation;
+pub use self::move_generation::*;
+
+mod pgn;
+pub use self::pgn::*;
+
+mod san;
+
+mod search;
+pub use self::search::*;
+
+mod square;
+pub use self::square::*;
+
+mod transposition;
+pub use self::transposition::*;
+
+mod zobrist;
@@ -0,0 +1,696 @@
+use hexx::Hex;
+
+use crate::{
+    components::{ChessPiece, PieceType},
+    data::{ChessBoard, ChessMove, GameResult, Square, Turn},
+};
+
+/// Steps through the edges of a hex, used by rooks, queens and kings.
+pub const ORTHOGONAL_DIRECTIONS: [Hex; 6] = [
+    Hex::new(0, 1),
+    Hex::new(1, 0),
+    Hex::new(1, -1),
+    Hex::new(0, -1),
+    Hex::new(-1, 0),
+    Hex::new(-1, 1),
+];
+
+/// Steps through the vertices of a hex, used by bishops, queens and kings.
+pub const DIAGONAL_DIRECTIONS: [Hex; 6] = [
+    Hex::new(1, 1),
+    Hex::new(2, -1),
+    Hex::new(1, -2),
+    Hex::new(-1, -1),
+    Hex::new(-2, 1),
+    Hex::new(-1, 2),
+];
+
+/// The twelve leaps of a knight: two orthogonal steps followed by one step
+/// turned by 60°, which lands on the non-corner hexes of the third ring.
+pub const KNIGHT_OFFSETS: [Hex; 12] = [
+    Hex::new(1, 2),
+    Hex::new(2, 1),
+    Hex::new(3, -1),
+    Hex::new(3, -2),
+    Hex::new(2, -3),
+    Hex::new(1, -3),
+    Hex::new(-1, -2),
+    Hex::new(-2, -1),
+    Hex::new(-3, 1),
+    Hex::new(-3, 2),
+    Hex::new(-2, 3),
+    Hex::new(-1, 3),
+];
+
+/// B1, C2, D3, E4, F5, G4, H3, I2 and K1.
+pub const WHITE_PAWN_START: [Hex; 9] = [
+    Hex::new(-4, -1),
+    Hex::new(-3, -1),
+    Hex::new(-2, -1),
+    Hex::new(-1, -1),
+    Hex::new(0, -1),
+    Hex::new(1, -2),
+    Hex::new(2, -3),
+    Hex::new(3, -4),
+    Hex::new(4, -5),
+];
+
+/// B7, C7, D7, E7, F7, G7, H7, I7 and K7.
+pub const BLACK_PAWN_START: [Hex; 9] = [
+    Hex::new(-4, 5),
+    Hex::new(-3, 4),
+    Hex::new(-2, 3),
+    Hex::new(-1, 2),
+    Hex::new(0, 1),
+    Hex::new(1, 1),
+    Hex::new(2, 1),
+    Hex::new(3, 1),
+    Hex::new(4, 1),
+];
+
+impl ChessBoard {
+    /// Every square the piece on `from` may move to, ignoring whether the move
+    /// leaves its own king in check.
+    pub fn piece_moves(&self, from: Square) -> Vec<Square> {
+        let Some(piece) = self.get_piece(from) else {
+            return Vec::new();
+        };
+
+        let mut moves = Vec::new();
+        match piece.piece_type() {
+            PieceType::Pawn => self.pawn_moves(from, piece, &mut moves),
+            PieceType::Knight => self.leaper_moves(from, piece, &KNIGHT_OFFSETS, &mut moves),
+            PieceType::Bishop => self.slider_moves(from, piece, &DIAGONAL_DIRECTIONS, &mut moves),
+            PieceType::Rook => self.slider_moves(from, piece, &ORTHOGONAL_DIRECTIONS, &mut moves),
+            PieceType::Queen => {
+                self.slider_moves(from, piece, &ORTHOGONAL_DIRECTIONS, &mut moves);
+                self.slider_moves(from, piece, &DIAGONAL_DIRECTIONS, &mut moves);
+            }
+            PieceType::King => {
+                self.leaper_moves(from, piece, &ORTHOGONAL_DIRECTIONS, &mut moves);
+                self.leaper_moves(from, piece, &DIAGONAL_DIRECTIONS, &mut moves);
+            }
+        }
+
+        moves
+    }
+
+    /// Every move available to one side, ignoring whether the move leaves its
+    /// own king in check.
+    pub fn pseudo_legal_moves(&self, black: bool) -> Vec<ChessMove> {
+        let mut moves = Vec::new();
+        for (from, piece) in self.pieces() {
+            if piece.is_black() != black {
+                continue;
+            }
+
+            let promotes = piece.piece_type() == PieceType::Pawn;
+            for to in self.piece_moves(from) {
+                if promotes && Self::is_promotion_square(to, black) {
+                    for promotion in PieceType::PROMOTIONS {
+                        moves.push(ChessMove::new(from, to).with_promotion(promotion));
+                    }
+                } else {
+                    moves.push(ChessMove::new(from, to));
+                }
+            }
+        }
+
+        moves
+    }
+
+    /// Whether `chess_move` is one of the moves generated for the piece it
+    /// moves, including a valid promotion choice when one is required.
+    pub fn is_pseudo_legal(&self, chess_move: &ChessMove) -> bool {
+        let Some(piece) = self.get_piece(chess_move.from) else {
+            return false;
+        };
+
+        if !self.piece_moves(chess_move.from).contains(&chess_move.to) {
+            return false;
+        }
+
+        let promotes = piece.piece_type() == PieceType::Pawn
+            && Self::is_promotion_square(chess_move.to, piece.is_black());
+        match chess_move.promotion {
+            None => !promotes,
+            Some(promotion) => promotes && promotion.is_promotion(),
+        }
+    }
+}
+
+impl ChessBoard {
+    pub fn king_square(&self, black: bool) -> Option<Square> {
+        self.pieces()
+            .find(|(_, piece)| *piece == ChessPiece::king(black))
+            .map(|(square, _)| square)
+    }
+
+    /// Whether any piece of the given colour could capture on `square`.
+    pub fn is_attacked(&self, square: Square, by_black: bool) -> bool {
+        for offset in KNIGHT_OFFSETS {
+            if square
+                .offset(offset)
+                .is_some_and(|from| self.get_piece(from) == Some(ChessPiece::knight(by_black)))
+            {
+                return true;
+            }
+        }
+
+        for direction in Self::pawn_capture_directions(by_black) {
+            if square
+                .offset(-direction)
+                .is_some_and(|from| self.get_piece(from) == Some(ChessPiece::pawn(by_black)))
+            {
+                return true;
+            }
+        }
+
+        let lines = [
+            (ORTHOGONAL_DIRECTIONS, PieceType::Rook),
+            (DIAGONAL_DIRECTIONS, PieceType::Bishop),
+        ];
+        for (directions, slider) in lines {
+            for direction in directions {
+                let mut next = square.offset(direction);
+                let mut adjacent = true;
+                while let Some(to) = next {
+                    if let Some(piece) = self.get_piece(to) {
+                        if piece.is_black() == by_black {
+                            match piece.piece_type() {
+                                PieceType::Queen => return true,
+                                PieceType::King if adjacent => return true,
+                                piece_type if piece_type == slider => return true,
+                                _ => {}
+                            }
+                        }
+                        break;
+                    }
+                    next = to.offset(direction);
+                    adjacent = false;
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn is_in_check(&self, black: bool) -> bool {
+        self.king_square(black)
+            .is_some_and(|king| self.is_attacked(king, !black))
+    }
+
+    /// Whether `chess_move` is pseudo-legal and does not leave the mover's
+    /// king attacked.
+    pub fn is_legal(&self, chess_move: &ChessMove) -> bool {
+        self.is_pseudo_legal(chess_move) && self.is_king_safe_after(chess_move)
+    }
+
+    pub fn legal_moves(&self, black: bool) -> Vec<ChessMove> {
+        self.pseudo_legal_moves(black)
+            .into_iter()
+            .filter(|chess_move| self.is_king_safe_after(chess_move))
+            .collect()
+    }
+
+    /// Every square the piece on `from` may legally move to.
+    pub fn legal_piece_moves(&self, from: Square) -> Vec<Square> {
+        self.piece_moves(from)
+            .into_iter()
+            .filter(|to| self.is_king_safe_after(&ChessMove::new(from, *to)))
+            .collect()
+    }
+
+    pub fn is_checkmate(&self, black: bool) -> bool {
+        self.is_in_check(black) && self.legal_moves(black).is_empty()
+    }
+
+    pub fn is_stalemate(&self, black: bool) -> bool {
+        !self.is_in_check(black) && self.legal_moves(black).is_empty()
+    }
+
+    /// The result of the game if the given side is to move and cannot.
+    pub fn game_result(&self, black: bool) -> Option<GameResult> {
+        if !self.legal_moves(black).is_empty() {
+            return None;
+        }
+
+        let winner = Turn::from_black(!black);
+        if self.is_in_check(black) {
+            Some(GameResult::Checkmate { winner })
+        } else {
+            Some(GameResult::Stalemate { winner })
+        }
+    }
+
+    /// Whether the given side has enough pieces left to ever mate. A lone
+    /// king, or a king with a single knight or bishop, cannot.
+    pub fn has_mating_material(&self, black: bool) -> bool {
+        let mut minors = 0;
+        for (_, piece) in self.pieces() {
+            if piece.is_black() != black {
+                continue;
+            }
+            match piece.piece_type() {
+                PieceType::King => {}
+                PieceType::Knight | PieceType::Bishop => minors += 1,
+                PieceType::Pawn | PieceType::Rook | PieceType::Queen => return true,
+            }
+        }
+
+        minors > 1
+    }
+
+    fn is_king_safe_after(&self, chess_move: &ChessMove) -> bool {
+        let Some(piece) = self.get_piece(chess_move.from) else {
+            return false;
+        };
+
+        let mut board = self.clone();
+        board.apply_move(chess_move);
+        !board.is_in_check(piece.is_black())
+    }
+}
+
+impl ChessBoard {
+    pub fn pawn_direction(black: bool) -> Hex {
+        if black {
+            Hex::new(0, -1)
+        } else {
+            Hex::new(0, 1)
+        }
+    }
+
+    /// The two directions a pawn captures in, each through an edge of its hex
+    /// and turned 60° from its direction of travel.
+    pub fn pawn_capture_directions(black: bool) -> [Hex; 2] {
+        if black {
+            [Hex::new(-1, 0), Hex::new(1, -1)]
+        } else {
+            [Hex::new(-1, 1), Hex::new(1, 0)]
+        }
+    }
+
+    /// Pawns promote on the far rim of every file, so the whole top (or
+    /// bottom) edge of the board rather than a single rank.
+    pub fn is_promotion_square(square: Square, black: bool) -> bool {
+        square.offset(Self::pawn_direction(black)).is_none()
+    }
+
+    /// Pawns may double step from any starting square of their own colour, so
+    /// a pawn that captured onto another starting square keeps that right.
+    pub fn is_pawn_start(square: Square, black: bool) -> bool {
+        if black {
+            BLACK_PAWN_START.contains(&square.hex())
+        } else {
+            WHITE_PAWN_START.contains(&square.hex())
+        }
+    }
+
+    fn pawn_moves(&self, from: Square, piece: ChessPiece, moves: &mut Vec<Square>) {
+        let black = piece.is_black();
+        let direction = Self::pawn_direction(black);
+
+        if let Some(forward) = from.offset(direction) {
+            if self.get_piece(forward).is_none() {
+                moves.push(forward);
+
+                if let Some(double) = forward.offset(direction) {
+                    if Self::is_pawn_start(from, black) && self.get_piece(double).is_none() {
+                        moves.push(double);
+                    }
+                }
+            }
+        }
+
+        for direction in Self::pawn_capture_directions(black) {
+            let Some(to) = from.offset(direction) else {
+                continue;
+            };
+            if self
+                .get_piece(to)
+                .is_some_and(|other| piece.is_opponent(other))
+                || self.is_en_passant(from, to)
+            {
+                moves.push(to);
+            }
+        }
+    }
+
+    /// Whether moving the piece on `from` to `to` captures a pawn that just
+    /// double stepped over `to`.
+    pub fn is_en_passant(&self, from: Square, to: Square) -> bool {
+        if self.en_passant() != Some(to) {
+            return false;
+        }
+
+        let Some(piece) = self.get_piece(from) else {
+            return false;
+        };
+        if piece.piece_type() != PieceType::Pawn {
+            return false;
+        }
+
+        let black = piece.is_black();
+        Self::pawn_capture_directions(black).contains(&(to.hex() - from.hex()))
+            && Self::en_passant_victim(to, black)
+                .is_some_and(|victim| self.get_piece(victim) == Some(ChessPiece::pawn(!black)))
+    }
+
+    /// The square of the pawn removed when a pawn of the given colour captures
+    /// en passant onto `target`.
+    pub fn en_passant_victim(target: Square, black: bool) -> Option<Square> {
+        target.offset(Self::pawn_direction(!black))
+    }
+
+    fn leaper_moves(
+        &self,
+        from: Square,
+        piece: ChessPiece,
+        offsets: &[Hex],
+        moves: &mut Vec<Square>,
+    ) {
+        for offset in offsets {
+            let Some(to) = from.offset(*offset) else {
+                continue;
+            };
+
+            match self.get_piece(to) {
+                Some(other) if !piece.is_opponent(other) => {}
+                _ => moves.push(to),
+            }
+        }
+    }
+
+    fn slider_moves(
+        &self,
+        from: Square,
+        piece: ChessPiece,
+        directions: &[Hex],
+        moves: &mut Vec<Square>,
+    ) {
+        for direction in directions {
+            let mut next = from.offset(*direction);
+            while let Some(to) = next {
+                match self.get_piece(to) {
+                    None => moves.push(to),
+                    Some(other) => {
+                        if piece.is_opponent(other) {
+                            moves.push(to);
+                        }
+                        break;
+                    }
+                }
+                next = to.offset(*direction);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Game;
+
+    fn square(notation: &str) -> Square {
+        notation.parse().unwrap()
+    }
+
+    /// A board holding only the two kings, off every line through the centre.
+    fn kings_only() -> ChessBoard {
+        let mut board = ChessBoard::empty();
+        board.set_piece_notation("B1", Some(ChessPiece::king(false)));
+        board.set_piece_notation("K1", Some(ChessPiece::king(true)));
+        board
+    }
+
+    #[test]
+    fn start_position_move_counts() {
+        let board = ChessBoard::default();
+        assert_eq!(board.pseudo_legal_moves(false).len(), 51);
+        assert_eq!(board.pseudo_legal_moves(true).len(), 51);
+        assert_eq!(board.legal_moves(false).len(), 51);
+        assert_eq!(board.legal_moves(true).len(), 51);
+    }
+
+    #[test]
+    fn piece_moves_from_the_centre() {
+        let counts = [
+            (ChessPiece::rook(false), 30),
+            (ChessPiece::bishop(false), 12),
+            (ChessPiece::queen(false), 42),
+            (ChessPiece::knight(false), 12),
+        ];
+        for (piece, count) in counts {
+            let mut board = kings_only();
+            board.set_piece_notation("F6", Some(piece));
+            assert_eq!(board.piece_moves(square("F6")).len(), count, "{piece:?}");
+        }
+
+        let mut board = kings_only();
+        board.set_piece_notation("B1", None);
+        board.set_piece_notation("F6", Some(ChessPiece::king(false)));
+        assert_eq!(board.piece_moves(square("F6")).len(), 12);
+    }
+
+    #[test]
+    fn sliders_stop_at_pieces() {
+        let mut board = kings_only();
+        board.set_piece_notation("F6", Some(ChessPiece::rook(false)));
+        board.set_piece_notation("F8", Some(ChessPiece::pawn(true)));
+        board.set_piece_notation("F4", Some(ChessPiece::pawn(false)));
+        let moves = board.piece_moves(square("F6"));
+        assert!(moves.contains(&square("F7")));
+        assert!(moves.contains(&square("F8")));
+        assert!(!moves.contains(&square("F9")));
+        assert!(moves.contains(&square("F5")));
+        assert!(!moves.contains(&square("F4")));
+    }
+
+    #[test]
+    fn pawns_double_step_only_from_the_start() {
+        let board = ChessBoard::default();
+        assert_eq!(
+            board.piece_moves(square("E4")),
+            vec![square("E5"), square("E6")]
+        );
+
+        let mut board = kings_only();
+        board.set_piece_notation("E5", Some(ChessPiece::pawn(false)));
+        assert_eq!(board.piece_moves(square("E5")), vec![square("E6")]);
+    }
+
+    #[test]
+    fn pawns_capture_through_their_forward_edges() {
+        let mut board = kings_only();
+        board.set_piece_notation("F5", Some(ChessPiece::pawn(false)));
+        board.set_piece_notation("E5", Some(ChessPiece::knight(true)));
+        board.set_piece_notation("G5", Some(ChessPiece::knight(true)));
+        board.set_piece_notation("F6", Some(ChessPiece::knight(true)));
+        let mut moves = board.piece_moves(square("F5"));
+        moves.sort_by_key(|square| square.index());
+        assert_eq!(moves, vec![square("E5"), square("G5")]);
+    }
+
+    /// White to move with a pawn one step from promotion, able to push to F11
+    /// or take the rook on E10.
+    fn promotion_position() -> ChessBoard {
+        let mut board = kings_only();
+        board.set_piece_notation("F10", Some(ChessPiece::pawn(false)));
+        board.set_piece_notation("E10", Some(ChessPiece::rook(true)));
+        board
+    }
+
+    #[test]
+    fn pawns_promote_to_each_piece() {
+        let board = promotion_position();
+        let pawn_moves: Vec<ChessMove> = board
+            .legal_moves(false)
+            .into_iter()
+            .filter(|chess_move| chess_move.from == square("F10"))
+            .collect();
+        assert_eq!(pawn_moves.len(), 8);
+        for to in [square("F11"), square("E10")] {
+            let promotions: Vec<PieceType> = pawn_moves
+                .iter()
+                .filter(|chess_move| chess_move.to == to)
+                .filter_map(|chess_move| chess_move.promotion)
+                .collect();
+            assert_eq!(promotions, PieceType::PROMOTIONS, "{to}");
+        }
+    }
+
+    #[test]
+    fn promotions_need_a_valid_piece() {
+        let mut game = Game::new(promotion_position());
+        let push = ChessMove::new(square("F10"), square("F11"));
+        for chess_move in [
+            push,
+            push.with_promotion(PieceType::King),
+            push.with_promotion(PieceType::Pawn),
+        ] {
+            assert!(game.play(chess_move).is_err(), "{chess_move:?}");
+        }
+        assert!(game.history.is_empty());
+
+        game.play(push.with_promotion(PieceType::Knight)).unwrap();
+        assert_eq!(
+            game.board.get_piece(square("F11")),
+            Some(ChessPiece::knight(false))
+        );
+    }
+
+    #[test]
+    fn promotion_captures_are_taken_back() {
+        let mut board = promotion_position();
+        let hash = board.hash(false);
+        let capture = ChessMove::new(square("F10"), square("E10")).with_promotion(PieceType::Queen);
+        let undo = board.apply_move(&capture).unwrap();
+        assert_eq!(
+            board.get_piece(square("E10")),
+            Some(ChessPiece::queen(false))
+        );
+        assert_eq!(board.get_piece(square("F10")), None);
+        assert_eq!(undo.captured, Some(ChessPiece::rook(true)));
+
+        board.unapply_move(&undo);
+        assert_eq!(
+            board.get_piece(square("F10")),
+            Some(ChessPiece::pawn(false))
+        );
+        assert_eq!(board.get_piece(square("E10")), Some(ChessPiece::rook(true)));
+        assert_eq!(board.hash(false), hash);
+    }
+
+    /// Black to move after the white pawn double stepped from E4 to E6 past
+    /// the black pawn on F6.
+    fn en_passant_game() -> Game {
+        let mut board = kings_only();
+        board.set_piece_notation("E4", Some(ChessPiece::pawn(false)));
+        board.set_piece_notation("F6", Some(ChessPiece::pawn(true)));
+        let mut game = Game::new(board);
+        game.play_san("e6").unwrap();
+        game
+    }
+
+    #[test]
+    fn en_passant_removes_the_pawn_that_double_stepped() {
+        let mut game = en_passant_game();
+        assert_eq!(game.board.en_passant(), Some(square("E5")));
+        let capture = ChessMove::new(square("F6"), square("E5"));
+        assert!(game.board.is_en_passant(capture.from, capture.to));
+
+        game.play(capture).unwrap();
+        assert_eq!(game.board.get_piece(square("E6")), None);
+        assert_eq!(
+            game.board.get_piece(square("E5")),
+            Some(ChessPiece::pawn(true))
+        );
+        assert_eq!(game.captured, vec![ChessPiece::pawn(false)]);
+        assert_eq!(game.board.en_passant(), None);
+    }
+
+    #[test]
+    fn en_passant_lasts_one_move() {
+        let mut game = en_passant_game();
+        game.play_san("Kk2").unwrap();
+        assert_eq!(game.board.en_passant(), None);
+        game.play_san("Kb2").unwrap();
+        assert!(!game.board.is_en_passant(square("F6"), square("E5")));
+        assert!(game
+            .parse_san("fxe5")
+            .is_err_and(|error| error.to_string().contains("no Pawn")));
+    }
+
+    #[test]
+    fn undoing_en_passant_restores_the_victim_and_the_square() {
+        let mut game = en_passant_game();
+        let hash = game.hash();
+        game.play(ChessMove::new(square("F6"), square("E5")))
+            .unwrap();
+        game.undo().unwrap();
+
+        assert_eq!(
+            game.board.get_piece(square("E6")),
+            Some(ChessPiece::pawn(false))
+        );
+        assert_eq!(
+            game.board.get_piece(square("F6")),
+            Some(ChessPiece::pawn(true))
+        );
+        assert_eq!(game.board.get_piece(square("E5")), None);
+        assert_eq!(game.board.en_passant(), Some(square("E5")));
+        assert!(game.captured.is_empty());
+        assert_eq!(game.hash(), hash);
+    }
+
+    #[test]
+    fn moves_into_check_are_not_legal() {
+        let mut board = kings_only();
+        board.set_piece_notation("B2", Some(ChessPiece::rook(false)));
+        board.set_piece_notation("B6", Some(ChessPiece::rook(true)));
+        // The rook is pinned to its king and may only move along the file.
+        let rook_moves: Vec<ChessMove> = board
+            .legal_moves(false)
+            .into_iter()
+            .filter(|chess_move| chess_move.from == square("B2"))
+            .collect();
+        assert!(rook_moves
+            .iter()
+            .all(|chess_move| chess_move.to.file() == 1));
+        assert_eq!(rook_moves.len(), 4);
+    }
+
+    #[test]
+    fn queen_and_rook_mate_the_king() {
+        let mut board = ChessBoard::empty();
+        board.set_piece_notation("G1", Some(ChessPiece::king(false)));
+        board.set_piece_notation("F11", Some(ChessPiece::king(true)));
+        board.set_piece_notation("F10", Some(ChessPiece::queen(false)));
+        board.set_piece_notation("F1", Some(ChessPiece::rook(false)));
+        assert!(board.is_in_check(true));
+        assert!(board.is_checkmate(true));
+        assert_eq!(
+            board.game_result(true),
+            Some(GameResult::Checkmate {
+                winner: Turn::White
+            })
+        );
+
+        // Without the rook the king takes the queen.
+        board.set_piece_notation("F1", None);
+        assert!(!board.is_checkmate(true));
+        assert_eq!(
+            board.legal_moves(true),
+            vec![ChessMove::new(square("F11"), square("F10"))]
+        );
+        assert_eq!(board.game_result(true), None);
+    }
+
+    #[test]
+    fn stalemate_is_won_by_the_stalemating_side() {
+        let mut board = ChessBoard::empty();
+        board.set_piece_notation("D8", Some(ChessPiece::king(false)));
+        board.set_piece_notation("F11", Some(ChessPiece::king(true)));
+        board.set_piece_notation("G8", Some(ChessPiece::queen(false)));
+        assert!(!board.is_in_check(true));
+        assert!(board.is_stalemate(true));
+        let result = board.game_result(true);
+        assert_eq!(
+            result,
+            Some(GameResult::Stalemate {
+                winner: Turn::White
+            })
+        );
+        assert_eq!(result.unwrap().score(Turn::White), 0.75);
+    }
+
+    #[test]
+    fn start_position_is_not_over() {
+        let board = ChessBoard::default();
+        for black in [false, true] {
+            assert!(!board.is_in_check(black));
+            assert_eq!(board.game_result(black), None);
+        }
+    }
+}
@@ -0,0 +1,512 @@
+use std::fmt;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::data::{Game, GameResult, Turn, GLINSKI_START_FEN};
+
+/// The tags every record starts with, in the order they are written.
+pub const PGN_ROSTER: [&str; 8] = [
+    "Event", "Site", "Date", "Round", "White", "Black", "Result", "Variant",
+];
+/// The value of a roster tag that is not known.
+const UNKNOWN_TAG: &str = "?";
+/// An unknown date, which keeps the `YYYY.MM.DD` shape.
+const UNKNOWN_DATE: &str = "????.??.??";
+
+/// A move of a game record with the comment written after it and any
+/// alternative lines that could have been played instead of it.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgnMove {
+    pub san: String,
+    pub comment: Option<String>,
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+/// One game of a PGN-style record: its tag pairs and its movetext.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    /// A comment written before the first move.
+    pub comment: Option<String>,
+    pub moves: Vec<PgnMove>,
+    pub result: String,
+}
+
+impl PgnGame {
+    /// Records the main line of `game` with the standard tags, adding `SetUp`
+    /// and `FEN` tags when it did not start from the Glinski position.
+    pub fn from_game(game: &Game) -> Self {
+        let result = pgn_result(game.result()).to_string();
+
+        let mut record = Self {
+            tags: PGN_ROSTER
+                .iter()
+                .map(|name| {
+                    let unknown = if *name == "Date" {
+                        UNKNOWN_DATE
+                    } else {
+                        UNKNOWN_TAG
+                    };
+                    (name.to_string(), unknown.to_string())
+                })
+                .collect(),
+            comment: None,
+            moves: game
+                .history_san()
+                .iter()
+                .map(|san| PgnMove {
+                    san: san.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            result: result.clone(),
+        };
+        record.set_tag("Result", &result);
+        record.set_tag("Variant", "Glinski");
+
+        let start = game.start_fen();
+        if start != GLINSKI_START_FEN {
+            record.set_tag("SetUp", "1");
+            record.set_tag("FEN", &start);
+        }
+
+        record
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Plays the main line from the `FEN` tag, or the Glinski start when there
+    /// is none. Variations and comments are kept on the record only.
+    pub fn to_game(&self) -> Result<Game> {
+        let mut game = match self.tag("FEN") {
+            Some(fen) => Game::from_fen(fen).context("the FEN tag is invalid")?,
+            None => Game::default(),
+        };
+
+        for pgn_move in self.moves.iter() {
+            let number = game.fullmove_number;
+            let dots = if game.turn.is_black() { "..." } else { "." };
+            game.play_san(&pgn_move.san)
+                .with_context(|| format!("move {number}{dots} {}", pgn_move.san))?;
+        }
+
+        Ok(game)
+    }
+}
+
+impl fmt::Display for PgnGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in self.tags.iter() {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(f, "[{name} \"{value}\"]")?;
+        }
+        writeln!(f)?;
+
+        let (turn, number) = self
+            .tag("FEN")
+            .and_then(|fen| Game::from_fen(fen).ok())
+            .map(|game| (game.turn, game.fullmove_number))
+            .unwrap_or((Turn::White, 1));
+
+        let mut tokens = Vec::new();
+        if let Some(comment) = &self.comment {
+            tokens.push(comment_token(comment));
+        }
+        write_moves(&self.moves, turn, number, &mut tokens);
+        tokens.push(self.result.clone());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + token.len() + 1 > 80 {
+                writeln!(f, "{line}")?;
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        writeln!(f, "{line}")
+    }
+}
+
+impl Game {
+    /// The position this game started from, before any move in its history.
+    pub fn start_fen(&self) -> String {
+        let mut game = self.clone();
+        while game.undo().is_some() {}
+        game.to_fen()
+    }
+
+    pub fn to_pgn(&self) -> String {
+        PgnGame::from_game(self).to_string()
+    }
+
+    /// Reads the first game of a PGN-style record.
+    pub fn from_pgn(text: &str) -> Result<Self> {
+        parse_pgn(text)?
+            .first()
+            .ok_or_else(|| anyhow!("the record holds no game"))?
+            .to_game()
+    }
+}
+
+/// The result token for a game, with Glinski stalemates scored 3/4 to 1/4.
+pub fn pgn_result(result: Option<GameResult>) -> &'static str {
+    match result {
+        Some(GameResult::Checkmate {
+            winner: Turn::White,
+        })
+        | Some(GameResult::Timeout {
+            winner: Turn::White,
+        }) => "1-0",
+        Some(GameResult::Checkmate {
+            winner: Turn::Black,
+        })
+        | Some(GameResult::Timeout {
+            winner: Turn::Black,
+        }) => "0-1",
+        Some(GameResult::Stalemate {
+            winner: Turn::White,
+        }) => "3/4-1/4",
+        Some(GameResult::Stalemate {
+            winner: Turn::Black,
+        }) => "1/4-3/4",
+        Some(GameResult::Draw) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+pub fn write_pgn(games: &[PgnGame]) -> String {
+    games
+        .iter()
+        .map(|game| game.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Reads every game of a PGN-style record, such as a whole tournament.
+pub fn parse_pgn(text: &str) -> Result<Vec<PgnGame>> {
+    let mut games = Vec::new();
+    let mut game = PgnGame::default();
+    let mut lines: Vec<Vec<PgnMove>> = vec![Vec::new()];
+    let mut in_movetext = false;
+
+    for token in tokenize(text)? {
+        match token {
+            Token::Tag(name, value) => {
+                if in_movetext {
+                    finish_game(&mut games, &mut game, &mut lines, String::from("*"))?;
+                    in_movetext = false;
+                }
+                game.set_tag(&name, &value);
+            }
+            Token::Comment(comment) => {
+                in_movetext = true;
+                let depth = lines.len();
+                let line = lines.last_mut().unwrap();
+                match line.last_mut() {
+                    Some(last) => append_comment(&mut last.comment, comment),
+                    None if depth == 1 => append_comment(&mut game.comment, comment),
+                    None => {
+                        let parent = &mut lines[depth - 2];
+                        if let Some(last) = parent.last_mut() {
+                            append_comment(&mut last.comment, comment);
+                        }
+                    }
+                }
+            }
+            Token::Move(san) => {
+                in_movetext = true;
+                lines.last_mut().unwrap().push(PgnMove {
+                    san,
+                    ..Default::default()
+                });
+            }
+            Token::Open => {
+                in_movetext = true;
+                if lines.last().unwrap().is_empty() {
+                    bail!("a variation must follow the move it replaces");
+                }
+                lines.push(Vec::new());
+            }
+            Token::Close => {
+                if lines.len() < 2 {
+                    bail!("')' closes a variation that was never opened");
+                }
+                let variation = lines.pop().unwrap();
+                if let Some(last) = lines.last_mut().unwrap().last_mut() {
+                    last.variations.push(variation);
+                }
+            }
+            Token::Result(result) => {
+                finish_game(&mut games, &mut game, &mut lines, result)?;
+                in_movetext = false;
+            }
+        }
+    }
+
+    if in_movetext || !game.tags.is_empty() {
+        finish_game(&mut games, &mut game, &mut lines, String::from("*"))?;
+    }
+
+    Ok(games)
+}
+
+fn finish_game(
+    games: &mut Vec<PgnGame>,
+    game: &mut PgnGame,
+    lines: &mut [Vec<PgnMove>],
+    result: String,
+) -> Result<()> {
+    if lines.len() > 1 {
+        bail!("a variation is still open at the end of the game");
+    }
+
+    let mut finished = std::mem::take(game);
+    finished.moves = std::mem::take(&mut lines[0]);
+    if finished.tag("Result").is_none() {
+        finished.set_tag("Result", &result);
+    }
+    finished.result = result;
+    games.push(finished);
+
+    Ok(())
+}
+
+fn append_comment(target: &mut Option<String>, comment: String) {
+    match target {
+        Some(existing) => {
+            existing.push(' ');
+            existing.push_str(&comment);
+        }
+        None => *target = Some(comment),
+    }
+}
+
+fn write_moves(moves: &[PgnMove], mut turn: Turn, mut number: u32, tokens: &mut Vec<String>) {
+    // A black move needs its number when it does not directly follow the
+    // white move of the same pair.
+    let mut needs_number = true;
+    for pgn_move in moves {
+        if !turn.is_black() {
+            tokens.push(format!("{number}."));
+        } else if needs_number {
+            tokens.push(format!("{number}..."));
+        }
+        tokens.push(pgn_move.san.clone());
+        needs_number = false;
+
+        if let Some(comment) = &pgn_move.comment {
+            tokens.push(comment_token(comment));
+            needs_number = true;
+        }
+
+        for variation in pgn_move.variations.iter() {
+            let mut variation_tokens = Vec::new();
+            write_moves(variation, turn, number, &mut variation_tokens);
+            if let Some(first) = variation_tokens.first_mut() {
+                first.insert(0, '(');
+            }
+            if let Some(last) = variation_tokens.last_mut() {
+                last.push(')');
+            }
+            tokens.extend(variation_tokens);
+            needs_number = true;
+        }
+
+        if turn.is_black() {
+            number += 1;
+        }
+        turn = turn.opponent();
+    }
+}
+
+/// A comment in braces. PGN has no way to escape a closing brace inside one,
+/// so any are dropped rather than ending the comment early.
+fn comment_token(comment: &str) -> String {
+    format!("{{{}}}", comment.replace('}', ""))
+}
+
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Move(String),
+    Open,
+    Close,
+    Result(String),
+}
+
+const RESULTS: [&str; 6] = ["1-0", "0-1", "1/2-1/2", "3/4-1/4", "1/4-3/4", "*"];
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '[' => {
+                let name: String = chars.by_ref().take_while(|c| !c.is_whitespace()).collect();
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if chars.next() != Some('"') {
+                    bail!("the value of tag '{name}' must be quoted");
+                }
+
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => value.extend(chars.next()),
+                        Some('"') => break,
+                        Some(c) => value.push(c),
+                        None => bail!("the value of tag '{name}' is never closed"),
+                    }
+                }
+
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if chars.next() != Some(']') {
+                    bail!("tag '{name}' is missing its closing ']'");
+                }
+                tokens.push(Token::Tag(name, value));
+            }
+            '{' => {
+                let mut comment = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => comment.push(c),
+                        None => bail!("a comment is never closed with '}}'"),
+                    }
+                }
+                tokens.push(Token::Comment(comment.trim().to_string()));
+            }
+            ';' => {
+                let comment: String = chars.by_ref().take_while(|c| *c != '\n').collect();
+                tokens.push(Token::Comment(comment.trim().to_string()));
+            }
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '$' => while chars.next_if(|c| c.is_ascii_digit()).is_some() {},
+            c => {
+                let mut word = String::from(c);
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !"[]{}();".contains(*c))
+                {
+                    word.push(c);
+                }
+
+                if RESULTS.contains(&word.as_str()) {
+                    tokens.push(Token::Result(word));
+                    continue;
+                }
+
+                // Move numbers such as `12.` or `12...` may be glued to the move.
+                let san = word.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+                if !san.is_empty() {
+                    tokens.push(Token::Move(san.to_string()));
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::ChessPiece,
+        data::{ChessBoard, ChessMove},
+    };
+
+    /// A game where the first move needs the rook's rank to tell it apart.
+    fn rank_disambiguated_game() -> Game {
+        let mut board = ChessBoard::empty();
+        board.set_piece_notation("A1", Some(ChessPiece::king(false)));
+        board.set_piece_notation("L1", Some(ChessPiece::king(true)));
+        board.set_piece_notation("F2", Some(ChessPiece::rook(false)));
+        board.set_piece_notation("F8", Some(ChessPiece::rook(false)));
+
+        let mut game = Game::new(board);
+        let rook_move = ChessMove::new("F2".parse().unwrap(), "F5".parse().unwrap());
+        assert_eq!(game.to_san(&rook_move), "R2f5");
+        game.play(rook_move).unwrap();
+        game.play_san("Kl2").unwrap();
+        game.play_san("R8f6").unwrap();
+        game
+    }
+
+    #[test]
+    fn game_round_trips() {
+        let game = rank_disambiguated_game();
+        let read = Game::from_pgn(&game.to_pgn()).unwrap();
+        assert_eq!(read.history, game.history);
+        assert_eq!(read.to_fen(), game.to_fen());
+    }
+
+    #[test]
+    fn record_with_comments_and_variations_round_trips() {
+        let mut first = PgnGame::from_game(&rank_disambiguated_game());
+        first.comment = Some(String::from("Two rooks on the f-file"));
+        first.moves[0].comment = Some(String::from("the lower rook"));
+        first.moves[0].variations.push(vec![
+            PgnMove {
+                san: String::from("R8f5"),
+                comment: Some(String::from("the upper rook")),
+                ..Default::default()
+            },
+            PgnMove {
+                san: String::from("Kl2"),
+                ..Default::default()
+            },
+        ]);
+
+        let mut start = Game::default();
+        start.play_san("f6").unwrap();
+        start.play_san("e7-e6").unwrap();
+        let second = PgnGame::from_game(&start);
+
+        let games = vec![first, second];
+        let text = write_pgn(&games);
+        assert!(text.contains("[Date \"????.??.??\"]"), "{text}");
+        let read = parse_pgn(&text).unwrap();
+        assert_eq!(read, games);
+        for (read, written) in read.iter().zip(games.iter()) {
+            assert_eq!(
+                read.to_game().unwrap().history,
+                written.to_game().unwrap().history
+            );
+        }
+    }
+
+    #[test]
+    fn closing_braces_in_comments_are_dropped() {
+        let mut record = PgnGame::from_game(&rank_disambiguated_game());
+        record.comment = Some(String::from("{nested} braces"));
+        record.moves[0].comment = Some(String::from("ends early} 1. Qe3"));
+
+        let read = parse_pgn(&record.to_string()).unwrap();
+        assert_eq!(read.len(), 1);
+        let read = &read[0];
+        assert_eq!(read.comment.as_deref(), Some("{nested braces"));
+        assert_eq!(read.moves[0].comment.as_deref(), Some("ends early 1. Qe3"));
+        assert_eq!(read.moves.len(), record.moves.len());
+        assert_eq!(
+            read.to_game().unwrap().history,
+            rank_disambiguated_game().history
+        );
+    }
+}
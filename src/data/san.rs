@@ -0,0 +1,357 @@
+use anyhow::{anyhow, bail, Result};
+
+use crate::{
+    components::PieceType,
+    data::{ChessBoard, ChessMove, Game, Square, GLINSKI_COLUMNS},
+};
+
+impl ChessBoard {
+    /// Writes a move in Glinski algebraic notation, such as `Ne3`, `Bxf7+`,
+    /// `exf5` or `c11=Q#`. Squares are lower case and the origin is only added
+    /// when another piece of the same kind could reach the same square.
+    pub fn to_san(&self, chess_move: &ChessMove) -> String {
+        let Some(piece) = self.get_piece(chess_move.from) else {
+            return format!(
+                "{}-{}",
+                san_square(chess_move.from),
+                san_square(chess_move.to)
+            );
+        };
+        let black = piece.is_black();
+        let capture = self.get_piece(chess_move.to).is_some()
+            || self.is_en_passant(chess_move.from, chess_move.to);
+
+        let mut san = String::new();
+        if piece.piece_type() == PieceType::Pawn {
+            if capture {
+                san.push(chess_move.from.file_char().to_ascii_lowercase());
+            }
+        } else {
+            san.push(piece.piece_type().to_char());
+
+            let rivals: Vec<Square> = self
+                .legal_moves(black)
+                .into_iter()
+                .filter(|other| {
+                    other.to == chess_move.to
+                        && other.from != chess_move.from
+                        && self.get_piece(other.from) == Some(piece)
+                })
+                .map(|other| other.from)
+                .collect();
+
+            if !rivals.is_empty() {
+                let from = chess_move.from;
+                if rivals.iter().all(|rival| rival.file() != from.file()) {
+                    san.push(from.file_char().to_ascii_lowercase());
+                } else if rivals.iter().all(|rival| rival.rank() != from.rank()) {
+                    san.push_str(&from.rank().to_string());
+                } else {
+                    san.push_str(&san_square(from));
+                }
+            }
+        }
+
+        if capture {
+            san.push('x');
+        }
+        san.push_str(&san_square(chess_move.to));
+
+        if let Some(promotion) = chess_move.promotion {
+            san.push('=');
+            san.push(promotion.to_char());
+        }
+
+        let mut board = self.clone();
+        if board.apply_move(chess_move).is_some() {
+            if board.is_checkmate(!black) {
+                san.push('#');
+            } else if board.is_in_check(!black) {
+                san.push('+');
+            }
+        }
+
+        san
+    }
+
+    /// Reads a move in Glinski algebraic notation for the given side. Accepts
+    /// both the short form (`Ne3`) and the long form (`Nd1-e3`), upper or lower
+    /// case squares, and ignores check, mate and annotation marks. Captures
+    /// need an `x`, and pawn captures the pawn's file too, as in `exd6`.
+    pub fn parse_san(&self, san: &str, black: bool) -> Result<ChessMove> {
+        let text = san.trim().trim_end_matches(['+', '#', '!', '?']);
+        if text.is_empty() {
+            bail!("'{san}' is not a move");
+        }
+
+        let (text, promotion) = match text.split_once('=') {
+            Some((text, promotion)) => {
+                let mut chars = promotion.chars();
+                let piece_type = chars
+                    .next()
+                    .and_then(|c| PieceType::from_char(c.to_ascii_uppercase()));
+                match piece_type {
+                    Some(piece_type) if chars.next().is_none() => (text, Some(piece_type)),
+                    _ => bail!("'{promotion}' in '{san}' is not a piece to promote to"),
+                }
+            }
+            None => (text, None),
+        };
+
+        // A leading piece letter is only the file of a square when the whole
+        // move is that square, as in `K1` against `K1f2` or `B2d4`, or when it
+        // starts the long form of a pawn move, as in `B1-B2`.
+        let long_pawn = text
+            .split_once('-')
+            .is_some_and(|(from, _)| from.parse::<Square>().is_ok());
+        let mut piece_type = PieceType::Pawn;
+        let mut rest = text;
+        if let Some(first) = text.chars().next() {
+            if let Some(piece) = PieceType::from_char(first) {
+                if text.parse::<Square>().is_err() && !long_pawn {
+                    piece_type = piece;
+                    rest = &text[first.len_utf8()..];
+                }
+            }
+        }
+
+        let capture = rest.contains('x');
+        let squares: String = rest.chars().filter(|c| !matches!(c, 'x' | '-')).collect();
+        let Some(split) = squares.rfind(|c: char| c.is_ascii_alphabetic()) else {
+            bail!("'{san}' does not name a destination square");
+        };
+        let (hint, target) = squares.split_at(split);
+        let to: Square = target
+            .parse()
+            .map_err(|error| anyhow!("'{san}' has a bad destination: {error}"))?;
+
+        let mut hint_chars = hint.chars().peekable();
+        let hint_file = match hint_chars.next_if(|c| c.is_ascii_alphabetic()) {
+            Some(c) => Some(
+                GLINSKI_COLUMNS
+                    .iter()
+                    .position(|&column| column == c.to_ascii_uppercase())
+                    .ok_or_else(|| anyhow!("'{c}' in '{san}' is not a file"))?,
+            ),
+            None => None,
+        };
+        let hint_rank: String = hint_chars.collect();
+        let hint_rank = if hint_rank.is_empty() {
+            None
+        } else {
+            Some(
+                hint_rank
+                    .parse::<i32>()
+                    .map_err(|_| anyhow!("'{hint_rank}' in '{san}' is not a rank"))?,
+            )
+        };
+
+        let matches_origin = |chess_move: &ChessMove| {
+            chess_move.to == to
+                && self
+                    .get_piece(chess_move.from)
+                    .is_some_and(|piece| piece.piece_type() == piece_type)
+                && hint_file.is_none_or(|file| chess_move.from.file() == file)
+                && hint_rank.is_none_or(|rank| chess_move.from.rank() == rank)
+        };
+
+        let mut candidates: Vec<ChessMove> = self
+            .legal_moves(black)
+            .into_iter()
+            .filter(matches_origin)
+            .collect();
+
+        if candidates.is_empty() {
+            if self.pseudo_legal_moves(black).iter().any(matches_origin) {
+                bail!("'{san}' would leave the king in check");
+            }
+            bail!("no {piece_type:?} can move to {}", san_square(to));
+        }
+
+        match promotion {
+            Some(promotion) => {
+                candidates.retain(|chess_move| chess_move.promotion == Some(promotion));
+                if candidates.is_empty() {
+                    bail!("'{san}' cannot promote");
+                }
+            }
+            None => {
+                if candidates
+                    .iter()
+                    .any(|chess_move| chess_move.promotion.is_some())
+                {
+                    bail!("'{san}' reaches the last rank and needs a promotion such as '{text}=Q'");
+                }
+            }
+        }
+
+        if candidates.len() > 1 {
+            let origins: Vec<String> = candidates
+                .iter()
+                .map(|chess_move| san_square(chess_move.from))
+                .collect();
+            bail!(
+                "'{san}' is ambiguous, it could move from {}",
+                origins.join(" or ")
+            );
+        }
+
+        let chess_move = candidates[0];
+        let captures = self.get_piece(chess_move.to).is_some()
+            || self.is_en_passant(chess_move.from, chess_move.to);
+        if capture && !captures {
+            bail!(
+                "'{san}' is marked as a capture but {} is empty",
+                san_square(to)
+            );
+        }
+        if captures && (!capture || (piece_type == PieceType::Pawn && hint_file.is_none())) {
+            bail!(
+                "'{san}' captures and is written '{}'",
+                self.to_san(&chess_move)
+            );
+        }
+
+        Ok(chess_move)
+    }
+}
+
+impl Game {
+    pub fn to_san(&self, chess_move: &ChessMove) -> String {
+        self.board.to_san(chess_move)
+    }
+
+    pub fn parse_san(&self, san: &str) -> Result<ChessMove> {
+        self.board.parse_san(san, self.turn.is_black())
+    }
+
+    pub fn play_san(&mut self, san: &str) -> Result<ChessMove> {
+        let chess_move = self.parse_san(san)?;
+        self.play(chess_move)?;
+        Ok(chess_move)
+    }
+}
+
+fn san_square(square: Square) -> String {
+    square.to_string().to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{components::ChessPiece, data::GLINSKI_START_FEN};
+
+    /// Writes every legal move of the position and reads it back, returning
+    /// what was written.
+    fn round_trip_all(board: &ChessBoard, black: bool) -> Vec<String> {
+        let mut sans = Vec::new();
+        for chess_move in board.legal_moves(black) {
+            let san = board.to_san(&chess_move);
+            let parsed = board
+                .parse_san(&san, black)
+                .unwrap_or_else(|error| panic!("'{san}' does not parse: {error}"));
+            assert_eq!(parsed, chess_move, "'{san}' reads back as another move");
+            sans.push(san);
+        }
+        sans
+    }
+
+    #[test]
+    fn rank_disambiguation_round_trips() {
+        let mut board = ChessBoard::empty();
+        board.set_piece_notation("A1", Some(ChessPiece::king(false)));
+        board.set_piece_notation("L1", Some(ChessPiece::king(true)));
+        board.set_piece_notation("F2", Some(ChessPiece::rook(false)));
+        board.set_piece_notation("F8", Some(ChessPiece::rook(false)));
+        board.set_piece_notation("B2", Some(ChessPiece::bishop(false)));
+        board.set_piece_notation("B5", Some(ChessPiece::bishop(false)));
+
+        let sans = round_trip_all(&board, false);
+        for piece in ['R', 'B'] {
+            assert!(
+                sans.iter().any(|san| {
+                    let mut chars = san.chars();
+                    chars.next() == Some(piece) && chars.next().is_some_and(|c| c.is_ascii_digit())
+                }),
+                "no move of {piece} needed its rank: {sans:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn every_move_of_random_games_round_trips() {
+        let mut rng = fastrand::Rng::with_seed(8);
+        for _ in 0..4 {
+            let mut game = Game::default();
+            for _ in 0..80 {
+                round_trip_all(&game.board, game.turn.is_black());
+                let moves = game.legal_moves();
+                if moves.is_empty() {
+                    break;
+                }
+                game.play(moves[rng.usize(..moves.len())]).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn history_is_written_as_moves_are_played() {
+        let mut game = Game::from_fen(&GLINSKI_START_FEN.replace(" 0 1", " 0 7")).unwrap();
+        for san in ["Nc3", "Nc6", "e5", "e7-e6"] {
+            game.play_san(san).unwrap();
+        }
+        assert_eq!(game.history_san(), ["Nc3", "Nc6", "e5", "e6"]);
+
+        game.take_back();
+        game.take_back();
+        assert_eq!(game.history_san(), ["Nc3", "Nc6"]);
+        game.redo().unwrap();
+        assert_eq!(game.history_san(), ["Nc3", "Nc6", "e5"]);
+
+        assert_eq!(game.start_fullmove_number, 7);
+        assert!(game.at_ply(0).history_san().is_empty());
+    }
+
+    #[test]
+    fn captures_need_an_x_and_the_pawn_file() {
+        let mut game = Game::default();
+        game.play_san("e6").unwrap();
+        game.play_san("d5").unwrap();
+        let en_passant = ChessMove::new("E6".parse().unwrap(), "D6".parse().unwrap());
+        for san in ["exd6", "e6xd6", "E6xD6", "exd6+"] {
+            assert_eq!(game.parse_san(san).unwrap(), en_passant, "{san}");
+        }
+        for san in ["d6", "xd6", "ed6", "e6-d6"] {
+            assert!(game.parse_san(san).is_err(), "'{san}' was accepted");
+        }
+    }
+
+    #[test]
+    fn upper_case_long_form_pawn_moves() {
+        let game = Game::default();
+        for (san, from, to) in [
+            ("B1-B2", "B1", "B2"),
+            ("K1-K3", "K1", "K3"),
+            ("E4-E6", "E4", "E6"),
+        ] {
+            let chess_move = game.parse_san(san).unwrap();
+            assert_eq!(
+                chess_move,
+                ChessMove::new(from.parse().unwrap(), to.parse().unwrap()),
+                "{san}"
+            );
+        }
+
+        // A piece letter before a whole square is still a piece.
+        let knight = game.parse_san("Nc3").unwrap();
+        let long = format!("N{}-C3", knight.from);
+        assert_eq!(game.parse_san(&long).unwrap(), knight);
+    }
+
+    #[test]
+    fn a_square_alone_is_a_pawn_move() {
+        let game = Game::default();
+        let chess_move = game.parse_san("B2").unwrap();
+        assert_eq!(chess_move.from, "B1".parse().unwrap());
+    }
+}
@@ -0,0 +1,352 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::PieceType,
+    data::{Bound, ChessBoard, ChessMove, Game, TranspositionTable},
+};
+
+/// The score of mating on the next move. Mates further away score one less
+/// per ply, so that the search prefers the quickest.
+pub const MATE_SCORE: i32 = 1_000_000;
+/// The score of stalemating on the next move. Below mate since a Glinski
+/// stalemate only scores 3/4, but above any material advantage.
+pub const STALEMATE_SCORE: i32 = 500_000;
+
+const INFINITY: i32 = i32::MAX - 1;
+/// Scores this close to mate or stalemate count plies from the root, and are
+/// stored relative to the position instead.
+const PLY_SCORE: i32 = STALEMATE_SCORE - 1_000;
+
+/// How far a search may go before it must answer.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// The deepest iteration, in plies.
+    pub depth: u32,
+    /// Positions to visit before stopping, keeping the last finished depth.
+    pub nodes: Option<u64>,
+    /// Root moves scoring within this much of the best move get an exact
+    /// score instead of a bound, so that they can be chosen instead of it.
+    pub margin: i32,
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self {
+            depth: 3,
+            nodes: None,
+            margin: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best_move: Option<ChessMove>,
+    /// The score of `best_move` for the side to move.
+    pub score: i32,
+    /// The deepest iteration that finished.
+    pub depth: u32,
+    /// Positions visited over every iteration.
+    pub nodes: u64,
+    /// Every legal move with its score from the deepest finished iteration,
+    /// best first. Scores within the margin of the best are exact, the others
+    /// are upper bounds below it.
+    pub root_moves: Vec<(ChessMove, i32)>,
+}
+
+/// Finds the best move for the side to move with negamax alpha-beta,
+/// deepening one ply at a time until `limits` are reached. Positions already
+/// in `table` are not searched again, and what is found is stored in it.
+/// Without a table every position is searched in full.
+pub fn search(
+    game: &Game,
+    limits: SearchLimits,
+    table: Option<&TranspositionTable>,
+) -> SearchResult {
+    if let Some(table) = table {
+        table.new_search();
+    }
+    let mut searcher = Searcher {
+        limits,
+        table,
+        nodes: 0,
+        aborted: false,
+    };
+    let mut board = game.board.clone();
+    let black = game.turn.is_black();
+
+    let mut result = SearchResult {
+        best_move: None,
+        score: 0,
+        depth: 0,
+        nodes: 0,
+        root_moves: Vec::new(),
+    };
+
+    let mut moves = legal_moves(&mut board, black);
+    order_moves(&board, &mut moves);
+    if let Some(first) = moves.first() {
+        result.best_move = Some(*first);
+    }
+
+    for depth in 1..=limits.depth.max(1) {
+        let mut best_score = -INFINITY;
+        let mut scores = Vec::with_capacity(moves.len());
+        for chess_move in moves.iter() {
+            let Some(undo) = board.apply_move(chess_move) else {
+                continue;
+            };
+            // One under the margin, so that a move failing low scores below
+            // it and is never mistaken for one within it.
+            let floor = best_score
+                .saturating_sub(limits.margin)
+                .saturating_sub(1)
+                .max(-INFINITY);
+            let score = -searcher.negamax(&mut board, !black, depth - 1, 1, -INFINITY, -floor);
+            board.unapply_move(&undo);
+
+            if searcher.aborted {
+                break;
+            }
+            scores.push((*chess_move, score));
+            best_score = best_score.max(score);
+        }
+
+        if searcher.aborted || scores.is_empty() {
+            break;
+        }
+
+        // The best moves of this iteration are tried first in the next one.
+        scores.sort_by_key(|(_, score)| -*score);
+        moves = scores.iter().map(|(chess_move, _)| *chess_move).collect();
+        result.best_move = Some(scores[0].0);
+        result.score = best_score;
+        result.depth = depth;
+        result.root_moves = scores;
+
+        // A forced mate found at this depth cannot get any shorter.
+        if best_score.abs() >= MATE_SCORE - depth as i32 {
+            break;
+        }
+    }
+
+    result.nodes = searcher.nodes;
+    result
+}
+
+struct Searcher<'a> {
+    limits: SearchLimits,
+    table: Option<&'a TranspositionTable>,
+    nodes: u64,
+    aborted: bool,
+}
+
+impl Searcher<'_> {
+    fn negamax(
+        &mut self,
+        board: &mut ChessBoard,
+        black: bool,
+        depth: u32,
+        ply: u32,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes) {
+            self.aborted = true;
+            return 0;
+        }
+
+        if depth == 0 {
+            return board.evaluate(black);
+        }
+
+        let key = board.hash(black);
+        let entry = self.table.and_then(|table| table.probe(key));
+        if let Some(entry) = entry.filter(|entry| entry.depth >= depth) {
+            let score = from_table(entry.score, ply);
+            let cutoff = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+            if cutoff {
+                return score;
+            }
+        }
+
+        let mut moves = board.pseudo_legal_moves(black);
+        order_moves(board, &mut moves);
+        // The best move found before for this position is tried first.
+        let hash_move = entry.and_then(|entry| entry.best_move);
+        if let Some(i) = moves
+            .iter()
+            .position(|chess_move| Some(*chess_move) == hash_move)
+        {
+            moves[..=i].rotate_right(1);
+        }
+
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        let mut best_move = None;
+        for chess_move in moves {
+            let Some(undo) = board.apply_move(&chess_move) else {
+                continue;
+            };
+            if board.is_in_check(black) {
+                board.unapply_move(&undo);
+                continue;
+            }
+
+            let score = -self.negamax(board, !black, depth - 1, ply + 1, -beta, -alpha);
+            board.unapply_move(&undo);
+            if self.aborted {
+                return 0;
+            }
+
+            if score > best {
+                best = score;
+                best_move = Some(chess_move);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        // No legal move: mated or stalemated.
+        if best == -INFINITY {
+            let ply = ply as i32;
+            return if board.is_in_check(black) {
+                -(MATE_SCORE - ply)
+            } else {
+                -(STALEMATE_SCORE - ply)
+            };
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        if let Some(table) = self.table {
+            table.store(key, depth, bound, to_table(best, ply), best_move);
+        }
+
+        best
+    }
+}
+
+/// Makes a mate or stalemate score count from the position at `ply` rather
+/// than from the root, so that it stays true wherever the position is met.
+fn to_table(score: i32, ply: u32) -> i32 {
+    if score >= PLY_SCORE {
+        score + ply as i32
+    } else if score <= -PLY_SCORE {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+/// Turns a score read from the table back into one counted from the root.
+fn from_table(score: i32, ply: u32) -> i32 {
+    if score >= PLY_SCORE {
+        score - ply as i32
+    } else if score <= -PLY_SCORE {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+fn legal_moves(board: &mut ChessBoard, black: bool) -> Vec<ChessMove> {
+    board
+        .pseudo_legal_moves(black)
+        .into_iter()
+        .filter(|chess_move| {
+            let Some(undo) = board.apply_move(chess_move) else {
+                return false;
+            };
+            let legal = !board.is_in_check(black);
+            board.unapply_move(&undo);
+            legal
+        })
+        .collect()
+}
+
+/// Puts captures first, most valuable victim and then least valuable
+/// attacker first, then promotions.
+fn order_moves(board: &ChessBoard, moves: &mut [ChessMove]) {
+    moves.sort_by_cached_key(|chess_move| {
+        let victim = board
+            .get_piece(chess_move.to)
+            .map(|piece| piece.value())
+            .unwrap_or(0);
+        let attacker = board
+            .get_piece(chess_move.from)
+            .map(|piece| piece.value())
+            .unwrap_or(0);
+        let promotion = chess_move
+            .promotion
+            .map(|piece_type| if piece_type == PieceType::Queen { 1 } else { 0 })
+            .unwrap_or(0);
+
+        if victim > 0 {
+            -(victim * 100 - attacker)
+        } else {
+            -promotion
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: SearchLimits = SearchLimits {
+        depth: 4,
+        nodes: None,
+        margin: 0,
+    };
+
+    /// A few moves into a game, so that pieces have met and positions repeat
+    /// through different move orders.
+    fn middle_game() -> Game {
+        let mut game = Game::default();
+        for san in ["e6", "d5", "Nc3", "Nc6"] {
+            game.play_san(san).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn table_reduces_nodes() {
+        let game = middle_game();
+        let without = search(&game, LIMITS, None);
+        let with = search(&game, LIMITS, Some(&TranspositionTable::new(16)));
+        assert!(
+            with.nodes < without.nodes,
+            "{} nodes with a table, {} without",
+            with.nodes,
+            without.nodes
+        );
+        assert_eq!(with.best_move, without.best_move);
+        assert_eq!(with.score, without.score);
+    }
+
+    #[test]
+    fn repeated_search_reuses_the_table() {
+        let game = middle_game();
+        let table = TranspositionTable::new(16);
+        let first = search(&game, LIMITS, Some(&table));
+        let second = search(&game, LIMITS, Some(&table));
+        assert!(second.nodes * 10 < first.nodes);
+        assert_eq!(second.best_move, first.best_move);
+
+        table.clear();
+        assert_eq!(search(&game, LIMITS, Some(&table)).nodes, first.nodes);
+    }
+}
@@ -0,0 +1,212 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{anyhow, bail};
+use bevy::prelude::*;
+use hexx::{shapes, Hex};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub const GLINSKI_COLUMNS: [char; 11] = ['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'K', 'L'];
+pub const GLINSKI_RADIUS: u32 = 5;
+const GLINSKI_COLUMN_OFFSET: i32 = GLINSKI_RADIUS as i32;
+const GLINSKI_ROW_OFFSET: i32 = GLINSKI_RADIUS as i32 + 1;
+/// The number of cells on the board.
+pub const SQUARE_COUNT: usize = 91;
+/// The index of the first square of each file, files holding 6 to 11 cells.
+const FILE_START: [usize; 11] = [0, 6, 13, 21, 30, 40, 51, 61, 70, 78, 85];
+
+/// One of the 91 cells of the Glinski board. Can only be built from a hex
+/// that lies on the board, so holding a `Square` means holding a valid cell.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Square(Hex);
+
+impl Square {
+    pub fn new(hex: Hex) -> Option<Self> {
+        if hex.ulength() <= GLINSKI_RADIUS {
+            Some(Self(hex))
+        } else {
+            None
+        }
+    }
+
+    pub fn all() -> impl Iterator<Item = Square> {
+        shapes::hexagon(Hex::ZERO, GLINSKI_RADIUS).map(Self)
+    }
+
+    pub fn hex(self) -> Hex {
+        self.0
+    }
+
+    /// The square `offset` away, if it is still on the board.
+    pub fn offset(self, offset: Hex) -> Option<Self> {
+        Self::new(self.0 + offset)
+    }
+
+    /// Index of the file, `A` being 0 and `L` being 10.
+    pub fn file(self) -> usize {
+        (self.0.x() + GLINSKI_COLUMN_OFFSET) as usize
+    }
+
+    /// The rank within the file, starting at 1 on the bottom edge.
+    pub fn rank(self) -> i32 {
+        if self.0.x() < 0 {
+            self.0.y() + self.0.x() + GLINSKI_ROW_OFFSET
+        } else {
+            self.0.y() + GLINSKI_ROW_OFFSET
+        }
+    }
+
+    /// A distinct number below [`SQUARE_COUNT`] for every square, counting up
+    /// each file from A1.
+    pub fn index(self) -> usize {
+        FILE_START[self.file()] + self.rank() as usize - 1
+    }
+
+    /// The square at the same place as seen from the other side of the board,
+    /// so that Black's pieces can share tables written for White.
+    pub fn mirror(self) -> Self {
+        Self(Hex::new(self.0.x(), -self.0.x() - self.0.y()))
+    }
+
+    /// Which of the three cell colours the square has. Each side has one
+    /// bishop on every colour.
+    pub fn shade(self) -> usize {
+        (self.0.x() - self.0.y()).rem_euclid(3) as usize
+    }
+
+    pub fn file_char(self) -> char {
+        GLINSKI_COLUMNS[self.file()]
+    }
+
+    pub fn from_file_rank(file: usize, rank: i32) -> Option<Self> {
+        if file >= GLINSKI_COLUMNS.len() {
+            return None;
+        }
+
+        let x = file as i32 - GLINSKI_COLUMN_OFFSET;
+        let y = if x < 0 {
+            rank - x - GLINSKI_ROW_OFFSET
+        } else {
+            rank - GLINSKI_ROW_OFFSET
+        };
+
+        Self::new(Hex::new(x, y))
+    }
+}
+
+impl From<Square> for Hex {
+    fn from(square: Square) -> Self {
+        square.0
+    }
+}
+
+impl TryFrom<Hex> for Square {
+    type Error = anyhow::Error;
+
+    fn try_from(hex: Hex) -> Result<Self, Self::Error> {
+        Self::new(hex).ok_or_else(|| anyhow!("{hex:?} is not on the board"))
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.file_char(), self.rank())
+    }
+}
+
+impl FromStr for Square {
+    type Err = anyhow::Error;
+
+    fn from_str(notation: &str) -> Result<Self, Self::Err> {
+        let mut chars = notation.chars();
+        let Some(column_char) = chars.next() else {
+            bail!("a square needs a file and a rank");
+        };
+
+        let Some(file) = GLINSKI_COLUMNS
+            .iter()
+            .position(|&c| c == column_char.to_ascii_uppercase())
+        else {
+            bail!("'{column_char}' is not a file, files run A to L without J");
+        };
+
+        // One or two digits without a sign or a leading zero, so that every
+        // square has exactly one spelling.
+        let rank = chars.as_str();
+        let digits = rank.len() <= 2 && rank.bytes().all(|byte| byte.is_ascii_digit());
+        if !digits || rank.is_empty() || rank.starts_with('0') {
+            bail!("'{notation}' does not end with a rank");
+        }
+        let rank: i32 = rank.parse()?;
+
+        Self::from_file_rank(file, rank).ok_or_else(|| anyhow!("{notation} is not on the board"))
+    }
+}
+
+impl Serialize for Square {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Square {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let notation = String::deserialize(deserializer)?;
+        notation.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notation_round_trips() {
+        assert_eq!(Square::all().count(), SQUARE_COUNT);
+        for square in Square::all() {
+            let notation = square.to_string();
+            assert_eq!(notation.parse::<Square>().unwrap(), square);
+            assert_eq!(notation.to_lowercase().parse::<Square>().unwrap(), square);
+        }
+    }
+
+    #[test]
+    fn serde_round_trips() {
+        for square in Square::all() {
+            let text = ron::to_string(&square).unwrap();
+            assert_eq!(text, format!("\"{square}\""));
+            assert_eq!(ron::from_str::<Square>(&text).unwrap(), square);
+        }
+        assert!(ron::from_str::<Square>("\"J5\"").is_err());
+    }
+
+    #[test]
+    fn squares_off_the_board_are_rejected() {
+        for notation in ["Z99", "J5", "A7", "F0", "L7", "F12", "", "F", "5"] {
+            assert!(
+                notation.parse::<Square>().is_err(),
+                "'{notation}' was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn ranks_have_one_spelling() {
+        for notation in ["F+5", "F05", "F-1", "F 5", "F5 ", "F100", "F٥"] {
+            assert!(
+                notation.parse::<Square>().is_err(),
+                "'{notation}' was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn indices_are_distinct_and_mirrors_pair_up() {
+        let mut seen = [false; SQUARE_COUNT];
+        for square in Square::all() {
+            assert!(!seen[square.index()], "{square} shares an index");
+            seen[square.index()] = true;
+            assert_eq!(square.mirror().mirror(), square);
+            assert_eq!(square.mirror().file(), square.file());
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::data::ChessMove;
+
+/// The table size used unless the settings ask for another.
+pub const DEFAULT_TABLE_MEGABYTES: usize = 16;
+
+/// How a stored score relates to the true score of the position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    /// The true score is at least this high: a move was good enough to cut
+    /// the search off.
+    Lower,
+    /// The true score is at most this high: no move raised alpha.
+    Upper,
+}
+
+/// What an earlier search learnt about one position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableEntry {
+    /// The full position hash, to tell apart positions sharing a slot.
+    pub key: u64,
+    /// Plies searched below the position.
+    pub depth: u32,
+    pub bound: Bound,
+    pub score: i32,
+    pub best_move: Option<ChessMove>,
+    /// The search that stored the entry, so that old entries are replaced
+    /// first.
+    generation: u8,
+}
+
+/// A fixed-size cache of searched positions keyed by their Zobrist hash.
+/// Clones share the same entries, so one table can be handed to several
+/// searches running on different threads.
+#[derive(Clone)]
+pub struct TranspositionTable {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    slots: Box<[Mutex<Option<TableEntry>>]>,
+    generation: AtomicU8,
+    megabytes: usize,
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_TABLE_MEGABYTES)
+    }
+}
+
+impl TranspositionTable {
+    /// An empty table taking about `megabytes` of memory, with room for at
+    /// least one entry.
+    pub fn new(megabytes: usize) -> Self {
+        let len = (megabytes * 1024 * 1024 / mem::size_of::<Mutex<Option<TableEntry>>>()).max(1);
+        let slots = (0..len).map(|_| Mutex::new(None)).collect();
+        Self {
+            inner: Arc::new(Inner {
+                slots,
+                generation: AtomicU8::new(0),
+                megabytes,
+            }),
+        }
+    }
+
+    pub fn megabytes(&self) -> usize {
+        self.inner.megabytes
+    }
+
+    /// The entry stored for the position, if it has not been replaced since.
+    pub fn probe(&self, key: u64) -> Option<TableEntry> {
+        let entry = *self
+            .slot(key)
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        entry.filter(|entry| entry.key == key)
+    }
+
+    /// Stores what was found about a position. The slot's entry is kept if it
+    /// is for another position, from the current search and deeper.
+    pub fn store(
+        &self,
+        key: u64,
+        depth: u32,
+        bound: Bound,
+        score: i32,
+        best_move: Option<ChessMove>,
+    ) {
+        let generation = self.inner.generation.load(Ordering::Relaxed);
+        let mut slot = self
+            .slot(key)
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let replace = slot
+            .is_none_or(|old| old.key == key || old.generation != generation || depth >= old.depth);
+        if !replace {
+            return;
+        }
+
+        // A shallower result for the same position still knows a good move.
+        let best_move = best_move.or_else(|| slot.filter(|old| old.key == key)?.best_move);
+        *slot = Some(TableEntry {
+            key,
+            depth,
+            bound,
+            score,
+            best_move,
+            generation,
+        });
+    }
+
+    /// Marks every stored entry as older than the ones the next search will
+    /// store, so that they are the first to be replaced.
+    pub fn new_search(&self) {
+        self.inner.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Forgets every position, for example between games.
+    pub fn clear(&self) {
+        for slot in self.inner.slots.iter() {
+            *slot.lock().unwrap_or_else(|error| error.into_inner()) = None;
+        }
+    }
+
+    fn slot(&self, key: u64) -> &Mutex<Option<TableEntry>> {
+        let slots = &self.inner.slots;
+        &slots[(key % slots.len() as u64) as usize]
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{
+    components::ChessPiece,
+    data::{Square, SQUARE_COUNT},
+};
+
+/// Random keys for every piece on every square, XORed together into a
+/// position hash. Generated at compile time from a fixed seed so that hashes
+/// are the same from one run to the next.
+const PIECE_KEYS: [[u64; 12]; SQUARE_COUNT] = {
+    let mut keys = [[0; 12]; SQUARE_COUNT];
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    let mut square = 0;
+    while square < SQUARE_COUNT {
+        let mut piece = 0;
+        while piece < 12 {
+            state = split_mix(state);
+            keys[square][piece] = state;
+            piece += 1;
+        }
+        square += 1;
+    }
+    keys
+};
+
+/// Keys for the square skipped by a pawn double step, when it can be taken
+/// en passant.
+const EN_PASSANT_KEYS: [u64; SQUARE_COUNT] = {
+    let mut keys = [0; SQUARE_COUNT];
+    let mut state = 0x2545_f491_4f6c_dd1d;
+    let mut square = 0;
+    while square < SQUARE_COUNT {
+        state = split_mix(state);
+        keys[square] = state;
+        square += 1;
+    }
+    keys
+};
+
+/// XORed in when Black is to move.
+pub const BLACK_TO_MOVE_KEY: u64 = split_mix(0x1b87_3593_cc9e_2d51);
+
+pub fn piece_key(square: Square, piece: ChessPiece) -> u64 {
+    let colour = if piece.is_black() { 6 } else { 0 };
+    PIECE_KEYS[square.index()][colour + piece.piece_type() as usize]
+}
+
+pub fn en_passant_key(square: Square) -> u64 {
+    EN_PASSANT_KEYS[square.index()]
+}
+
+/// One step of the SplitMix64 generator.
+const fn split_mix(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
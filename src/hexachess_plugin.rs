@@ -2,8 +2,17 @@ use bevy::prelude::*;
 
 use crate::{
     components::ChessPiece,
-    resources::{ChessTextures, HighlightedHexes},
-    systems::{draw_highlights, handle_input, highlight_hexes, spawn_camera, spawn_map},
+    resources::{
+        AppState, BoardOrientation, ChessTextures, ComputerPlayer, GameSettings, GameState,
+        HighlightedHexes, HistoryView, PendingPromotion,
+    },
+    systems::{
+        auto_orient_board, check_game_over, despawn_map, draw_highlights, finish_computer_move,
+        flip_board, handle_input, highlight_hexes, menu_buttons, move_list_input, orient_board,
+        persistent_highlights, promotion_picker, show_history_position, spawn_camera, spawn_clocks,
+        spawn_game_over_screen, spawn_main_menu, spawn_map, spawn_move_list, spawn_pause_menu,
+        start_computer_move, tick_clocks, toggle_pause, undo_redo, update_clocks, update_move_list,
+    },
 };
 
 pub struct HexachessPlugin;
@@ -12,11 +21,64 @@ impl Plugin for HexachessPlugin {
         app.register_type::<ChessPiece>();
         app.init_resource::<ChessTextures>();
         app.init_resource::<HighlightedHexes>();
+        app.init_resource::<PendingPromotion>();
+        app.init_resource::<HistoryView>();
+        app.init_resource::<GameSettings>();
+        app.init_resource::<BoardOrientation>();
+        app.init_resource::<ComputerPlayer>();
 
-        app.add_systems(Startup, (spawn_camera, spawn_map));
+        app.init_state::<AppState>();
+        app.add_sub_state::<GameState>();
+        app.enable_state_scoped_entities::<AppState>();
+        app.enable_state_scoped_entities::<GameState>();
+
+        app.add_systems(Startup, spawn_camera);
+        app.add_systems(OnEnter(AppState::MainMenu), spawn_main_menu);
+        app.add_systems(
+            OnEnter(AppState::InGame),
+            (spawn_map, spawn_move_list, spawn_clocks, auto_orient_board),
+        );
+        app.add_systems(OnExit(AppState::InGame), despawn_map);
+        app.add_systems(OnEnter(GameState::Paused), spawn_pause_menu);
+        app.add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen);
+
+        app.add_systems(Update, menu_buttons);
+        app.add_systems(
+            Update,
+            flip_board
+                .before(orient_board)
+                .run_if(in_state(AppState::InGame)),
+        );
+        app.add_systems(
+            Update,
+            toggle_pause
+                .before(promotion_picker)
+                .run_if(in_state(AppState::InGame)),
+        );
+        app.add_systems(
+            Update,
+            (
+                tick_clocks,
+                move_list_input,
+                show_history_position,
+                undo_redo,
+                handle_input,
+                promotion_picker,
+                start_computer_move,
+                finish_computer_move,
+                highlight_hexes,
+                persistent_highlights,
+                draw_highlights,
+                check_game_over,
+            )
+                .chain()
+                .run_if(in_state(GameState::Playing)),
+        );
         app.add_systems(
             Update,
-            (handle_input, highlight_hexes, draw_highlights).chain(),
+            (update_move_list, update_clocks, orient_board)
+                .after(check_game_over)
+                .run_if(in_state(AppState::InGame)),
         );
     }
 }
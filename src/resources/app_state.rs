@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+#[derive(States, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    InGame,
+}
+
+/// What is happening while a game is on screen. Only exists in
+/// [`AppState::InGame`] and starts in `Playing` every time a game starts.
+#[derive(SubStates, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[source(AppState = AppState::InGame)]
+pub enum GameState {
+    #[default]
+    Playing,
+    Paused,
+    GameOver,
+}
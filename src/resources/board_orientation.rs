@@ -0,0 +1,22 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+/// Which side of the board is at the bottom of the screen.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct BoardOrientation {
+    /// Black is at the bottom when set.
+    pub flipped: bool,
+}
+
+impl BoardOrientation {
+    /// The rotation of the camera, and of everything kept upright with it.
+    pub fn rotation(&self) -> Quat {
+        if self.flipped {
+            Quat::from_rotation_z(PI)
+        } else {
+            Quat::IDENTITY
+        }
+    }
+}
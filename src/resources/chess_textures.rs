@@ -1,4 +1,6 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::components::ChessPiece;
 
 #[derive(Resource, Reflect)]
 #[reflect(Resource)]
@@ -8,7 +10,28 @@ pub struct ChessTextures {
 }
 
 impl ChessTextures {
-    pub fn sprite(&self) {}
+    pub fn sprite(&self, piece: ChessPiece) -> Sprite {
+        Sprite {
+            image: self.handle.clone_weak(),
+            texture_atlas: Some(TextureAtlas {
+                layout: self.layout.clone_weak(),
+                index: piece.image_index(),
+            }),
+            custom_size: Some(Vec2::splat(64.0)),
+            anchor: Anchor::Custom(Vec2::new(0.0, -0.2)),
+            ..Default::default()
+        }
+    }
+
+    pub fn image_node(&self, piece: ChessPiece) -> ImageNode {
+        ImageNode::from_atlas_image(
+            self.handle.clone_weak(),
+            TextureAtlas {
+                layout: self.layout.clone_weak(),
+                index: piece.image_index(),
+            },
+        )
+    }
 }
 
 impl FromWorld for ChessTextures {
@@ -0,0 +1,20 @@
+use bevy::{prelude::*, tasks::Task};
+
+use crate::data::{ChessMove, SearchResult, TranspositionTable};
+
+/// The search the computer is running on the task pool, if it is thinking.
+#[derive(Resource, Default)]
+pub struct ComputerPlayer {
+    pub task: Option<Task<SearchResult>>,
+    /// The moves played when the search started. Its answer is dropped if the
+    /// game has moved on since, for example after an undo.
+    pub history: Vec<ChessMove>,
+    /// Positions searched so far this game, shared with the running search.
+    pub table: TranspositionTable,
+}
+
+impl ComputerPlayer {
+    pub fn is_thinking(&self) -> bool {
+        self.task.is_some()
+    }
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use crate::data::{Difficulty, TimeControl, Turn, DEFAULT_TABLE_MEGABYTES};
+
+/// How the next game is played, chosen before it starts.
+#[derive(Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct GameSettings {
+    /// The side played by the computer, `None` when two people play.
+    pub computer: Option<Turn>,
+    /// Turn the board so that the player's side is at the bottom when a game
+    /// against the computer starts.
+    pub auto_orient: bool,
+    /// The clock both sides play on, `None` for untimed games.
+    pub time_control: Option<TimeControl>,
+    /// How well the computer plays.
+    pub difficulty: Difficulty,
+    /// Memory the computer may use to remember searched positions, in
+    /// megabytes.
+    pub table_megabytes: usize,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            computer: None,
+            auto_orient: true,
+            time_control: None,
+            difficulty: Difficulty::default(),
+            table_megabytes: DEFAULT_TABLE_MEGABYTES,
+        }
+    }
+}
+
+impl GameSettings {
+    pub fn is_computer(&self, turn: Turn) -> bool {
+        self.computer == Some(turn)
+    }
+}
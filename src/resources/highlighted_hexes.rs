@@ -1,75 +1,168 @@
 use bevy::prelude::*;
-use hexx::Hex;
 
-use crate::resources::Map;
+use crate::{data::Square, resources::Map};
 
 #[derive(Resource, Reflect, Default)]
 #[reflect(Resource)]
 pub struct HighlightedHexes {
-    pub hovered: Hex,
-    pub path: Vec<Hex>,
-    pub attack: Vec<Hex>,
+    /// The square under the cursor, `None` when it is off the board or after a
+    /// reset.
+    pub hovered: Option<Square>,
+    /// The square of the piece picked up by the player, whose moves are shown
+    /// in `path` and `attack` until it is played or dropped.
+    pub selected: Option<Square>,
+    pub path: Vec<Square>,
+    pub attack: Vec<Square>,
+    /// The from and to squares of the last move. Kept when the hover changes.
+    pub last_move: Option<(Square, Square)>,
+    /// The square of the king in check. Kept when the hover changes.
+    pub check: Option<Square>,
 }
 
 impl HighlightedHexes {
     pub fn reset(&mut self, commands: &mut Commands, map: &Map) {
-        // reset hovered
-        if let Some(entity) = map.tile_entities.get(&self.hovered).copied() {
-            let color = map.get_tile_color(self.hovered);
-            commands.entity(entity).insert(MeshMaterial2d(color));
-        }
-        self.hovered = Hex::ZERO;
+        self.reset_hovered(commands, map);
+        self.selected = None;
 
-        // reset path
-        for (entity, hex) in self
-            .path
-            .iter()
-            .filter_map(|hex| map.tile_entities.get(hex).map(|entity| (*entity, *hex)))
-        {
-            let color = map.get_tile_color(hex);
-            commands.entity(entity).insert(MeshMaterial2d(color));
+        // reset path and attack
+        for square in self.path.iter().chain(self.attack.iter()) {
+            if let Some(entity) = map.tile_entities.get(&square.hex()).copied() {
+                let color = self.base_material(map, *square);
+                commands.entity(entity).insert(MeshMaterial2d(color));
+            }
         }
         self.path.clear();
+        self.attack.clear();
+    }
 
-        // reset attack
-        for (entity, hex) in self
-            .attack
-            .iter()
-            .filter_map(|hex| map.tile_entities.get(hex).map(|entity| (*entity, *hex)))
+    pub fn reset_hovered(&mut self, commands: &mut Commands, map: &Map) {
+        if let Some(square) = self.hovered.take() {
+            if let Some(entity) = map.tile_entities.get(&square.hex()).copied() {
+                let color = self.base_material(map, square);
+                commands.entity(entity).insert(MeshMaterial2d(color));
+            }
+        }
+    }
+
+    /// The material `square` goes back to when it stops being hovered or
+    /// shown as a move: its persistent highlight, or the tile colour.
+    pub fn base_material(&self, map: &Map, square: Square) -> Handle<ColorMaterial> {
+        if self.check == Some(square) {
+            map.check_material.clone_weak()
+        } else if self
+            .last_move
+            .is_some_and(|(from, to)| square == from || square == to)
         {
-            let color = map.get_tile_color(hex);
-            commands.entity(entity).insert(MeshMaterial2d(color));
+            map.last_move_material.clone_weak()
+        } else {
+            map.get_tile_color(square.hex())
         }
-        self.attack.clear();
     }
 
-    pub fn hover(&mut self, hex: Hex) {
-        self.hovered = hex;
+    /// The squares of the last move and the king in check.
+    fn persistent_squares(&self) -> impl Iterator<Item = Square> {
+        self.last_move
+            .into_iter()
+            .flat_map(|(from, to)| [from, to])
+            .chain(self.check)
+    }
+
+    /// Replaces the last move and check highlights.
+    pub fn set_persistent(
+        &mut self,
+        commands: &mut Commands,
+        map: &Map,
+        last_move: Option<(Square, Square)>,
+        check: Option<Square>,
+    ) {
+        for square in self.persistent_squares() {
+            if let Some(entity) = map.tile_entities.get(&square.hex()).copied() {
+                let color = map.get_tile_color(square.hex());
+                commands.entity(entity).insert(MeshMaterial2d(color));
+            }
+        }
+
+        self.last_move = last_move;
+        self.check = check;
+    }
+
+    pub fn hover(&mut self, square: Square) {
+        self.hovered = Some(square);
     }
 
-    pub fn add_to_path(&mut self, hex: Hex) {
-        self.path.push(hex);
+    pub fn select(&mut self, square: Square) {
+        self.selected = Some(square);
     }
 
-    pub fn add_to_attack(&mut self, hex: Hex) {
-        self.attack.push(hex);
+    /// Selects the piece on `square` if it belongs to the side to move and
+    /// shows its legal moves, returning whether it was selected.
+    pub fn select_piece(&mut self, commands: &mut Commands, map: &Map, square: Square) -> bool {
+        self.reset(commands, map);
+
+        let board = &map.game.board;
+        match board.get_piece(square) {
+            Some(piece) if piece.is_black() == map.game.turn.is_black() => {}
+            _ => return false,
+        }
+
+        self.select(square);
+        self.show_moves(map, square);
+        self.hover(square);
+
+        true
+    }
+
+    /// Adds the squares the piece on `square` can move to to `path`, and the
+    /// enemy pieces it can capture to `attack`.
+    pub fn show_moves(&mut self, map: &Map, square: Square) {
+        let board = &map.game.board;
+        for to in board.legal_piece_moves(square) {
+            if board.get_piece(to).is_some() || board.is_en_passant(square, to) {
+                self.add_to_attack(to);
+            } else {
+                self.add_to_path(to);
+            }
+        }
+    }
+
+    /// Whether the selected piece can move to `square`.
+    pub fn is_destination(&self, square: Square) -> bool {
+        self.selected.is_some() && (self.path.contains(&square) || self.attack.contains(&square))
+    }
+
+    pub fn add_to_path(&mut self, square: Square) {
+        self.path.push(square);
+    }
+
+    pub fn add_to_attack(&mut self, square: Square) {
+        self.attack.push(square);
     }
 
     pub fn draw(&self, commands: &mut Commands, map: &Map) {
-        if let Some(entity) = map.tile_entities.get(&self.hovered).copied() {
+        for square in self.persistent_squares() {
+            if let Some(entity) = map.tile_entities.get(&square.hex()).copied() {
+                let color = self.base_material(map, square);
+                commands.entity(entity).insert(MeshMaterial2d(color));
+            }
+        }
+
+        if let Some(entity) = self
+            .hovered
+            .and_then(|square| map.tile_entities.get(&square.hex()).copied())
+        {
             let color = map.hovered_material.clone_weak();
             commands.entity(entity).insert(MeshMaterial2d(color));
         }
 
-        for hex in self.path.iter() {
-            if let Some(entity) = map.tile_entities.get(hex).copied() {
+        for square in self.path.iter() {
+            if let Some(entity) = map.tile_entities.get(&square.hex()).copied() {
                 let color = map.path_material.clone_weak();
                 commands.entity(entity).insert(MeshMaterial2d(color));
             }
         }
 
-        for hex in self.attack.iter() {
-            if let Some(entity) = map.tile_entities.get(hex).copied() {
+        for square in self.attack.iter() {
+            if let Some(entity) = map.tile_entities.get(&square.hex()).copied() {
                 let color = map.attack_material.clone_weak();
                 commands.entity(entity).insert(MeshMaterial2d(color));
             }
@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+
+/// Which position of the game the board shows. `None` follows the game,
+/// `Some(ply)` shows the position after the first `ply` moves, read only.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct HistoryView {
+    pub ply: Option<usize>,
+}
+
+impl HistoryView {
+    pub fn is_live(&self) -> bool {
+        self.ply.is_none()
+    }
+
+    /// Shows the position after `ply` moves of a game of `len` moves,
+    /// following the game again once `ply` reaches the latest move.
+    pub fn show(&mut self, ply: usize, len: usize) {
+        self.ply = if ply >= len { None } else { Some(ply) };
+    }
+}
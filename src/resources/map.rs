@@ -1,7 +1,11 @@
+use anyhow::Result;
 use bevy::{prelude::*, utils::HashMap};
-use hexx::{Hex, HexLayout};
+use hexx::Hex;
 
-use crate::data::ChessBoard;
+use crate::{
+    data::{ChessMove, Game, Square},
+    resources::ChessTextures,
+};
 
 #[derive(Default, Reflect, Clone, Copy, PartialEq, Eq)]
 pub enum TileColor {
@@ -14,7 +18,7 @@ pub enum TileColor {
 #[derive(Resource, Reflect, Default)]
 #[reflect(Resource)]
 pub struct Map {
-    pub board: ChessBoard,
+    pub game: Game,
     pub tile_entities: HashMap<Hex, Entity>,
     pub piece_entities: HashMap<Hex, Entity>,
 
@@ -25,6 +29,8 @@ pub struct Map {
     pub hovered_material: Handle<ColorMaterial>,
     pub path_material: Handle<ColorMaterial>,
     pub attack_material: Handle<ColorMaterial>,
+    pub last_move_material: Handle<ColorMaterial>,
+    pub check_material: Handle<ColorMaterial>,
     // pub default_material: Handle<ColorMaterial>,
 }
 
@@ -45,4 +51,58 @@ impl Map {
             _ => unreachable!(),
         }
     }
+
+    pub fn despawn_pieces(&mut self, commands: &mut Commands) {
+        for (_, entity) in self.piece_entities.drain() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    /// The square of the piece sprite `entity`.
+    pub fn piece_square(&self, entity: Entity) -> Option<Square> {
+        self.piece_entities
+            .iter()
+            .find(|(_, piece_entity)| **piece_entity == entity)
+            .and_then(|(hex, _)| Square::new(*hex))
+    }
+
+    /// Plays a move in the game and moves, removes or promotes the piece
+    /// sprites to match.
+    pub fn play_move(
+        &mut self,
+        commands: &mut Commands,
+        chess_textures: &ChessTextures,
+        chess_move: ChessMove,
+    ) -> Result<()> {
+        self.game.play(chess_move)?;
+        let Some(undo) = self.game.last_move().copied() else {
+            return Ok(());
+        };
+
+        if undo.captured.is_some() {
+            if let Some(entity) = self.piece_entities.remove(&undo.captured_square.hex()) {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        let position = self.game.board.layout.hex_to_world_pos(undo.to.hex());
+        let transform = Transform::from_xyz(position.x, position.y, 0.0);
+        if let Some(entity) = self.piece_entities.remove(&undo.from.hex()) {
+            match self.game.board.get_piece(undo.to) {
+                Some(piece) if chess_move.promotion.is_some() => {
+                    commands.entity(entity).insert((
+                        piece,
+                        chess_textures.sprite(piece),
+                        transform,
+                    ));
+                }
+                _ => {
+                    commands.entity(entity).insert(transform);
+                }
+            }
+            self.piece_entities.insert(undo.to.hex(), entity);
+        }
+
+        Ok(())
+    }
 }
@@ -6,3 +6,21 @@ pub use self::highlighted_hexes::*;
 
 mod chess_textures;
 pub use self::chess_textures::*;
+
+mod pending_promotion;
+pub use self::pending_promotion::*;
+
+mod app_state;
+pub use self::app_state::*;
+
+mod history_view;
+pub use self::history_view::*;
+
+mod game_settings;
+pub use self::game_settings::*;
+
+mod board_orientation;
+pub use self::board_orientation::*;
+
+mod computer_player;
+pub use self::computer_player::*;
@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+
+use crate::data::ChessMove;
+
+/// A pawn move waiting for the player to choose what the pawn becomes. The
+/// move is only played once a piece is picked.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct PendingPromotion {
+    pub chess_move: Option<ChessMove>,
+}
+
+impl PendingPromotion {
+    pub fn is_pending(&self) -> bool {
+        self.chess_move.is_some()
+    }
+}
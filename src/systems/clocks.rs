@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::ClockDisplay,
+    data::{format_clock, Turn},
+    resources::{AppState, GameSettings, Map},
+};
+
+const CLOCK_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+const RUNNING_CLOCK_COLOR: Color = Color::srgb(0.2, 0.6, 0.3);
+
+/// Spawns both clocks on the left of the board for timed games.
+pub fn spawn_clocks(mut commands: Commands, game_settings: Res<GameSettings>) {
+    if game_settings.time_control.is_none() {
+        return;
+    }
+
+    commands
+        .spawn((
+            StateScoped(AppState::InGame),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Px(0.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                row_gap: Val::Px(8.0),
+                ..Default::default()
+            },
+        ))
+        .with_children(|parent| {
+            for side in [Turn::Black, Turn::White] {
+                parent
+                    .spawn((
+                        ClockDisplay(side),
+                        Node {
+                            width: Val::Px(160.0),
+                            padding: UiRect::all(Val::Px(8.0)),
+                            justify_content: JustifyContent::Center,
+                            ..Default::default()
+                        },
+                        BackgroundColor(CLOCK_COLOR),
+                    ))
+                    .with_child((
+                        Text::default(),
+                        TextFont {
+                            font_size: 32.0,
+                            ..Default::default()
+                        },
+                    ));
+            }
+        });
+}
+
+/// Runs the clock of the side to move.
+pub fn tick_clocks(time: Res<Time>, mut map: ResMut<Map>) {
+    // Ticking every frame must not make the board look changed.
+    map.bypass_change_detection().game.tick(time.delta());
+}
+
+/// Shows the time left to each side and highlights the running clock.
+pub fn update_clocks(
+    map: Res<Map>,
+    mut displays: Query<(&ClockDisplay, &mut BackgroundColor, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    let Some(clock) = map.game.clock.as_ref() else {
+        return;
+    };
+
+    for (display, mut background, children) in displays.iter_mut() {
+        let color = if clock.running() == Some(display.0) {
+            RUNNING_CLOCK_COLOR
+        } else {
+            CLOCK_COLOR
+        };
+        if background.0 != color {
+            background.0 = color;
+        }
+
+        let mut labels = texts.iter_many_mut(children);
+        while let Some(mut text) = labels.fetch_next() {
+            text.0 = format_clock(clock.remaining(display.0));
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use bevy::{
+    prelude::*,
+    tasks::{block_on, AsyncComputeTaskPool},
+};
+
+use crate::{
+    data::{search, TranspositionTable},
+    resources::{ChessTextures, ComputerPlayer, GameSettings, HighlightedHexes, HistoryView, Map},
+};
+
+/// Starts a search on the task pool when it is the computer's turn, so that
+/// the window keeps running while it thinks.
+pub fn start_computer_move(
+    map: Res<Map>,
+    game_settings: Res<GameSettings>,
+    mut computer_player: ResMut<ComputerPlayer>,
+    mut mated: Local<bool>,
+) {
+    // Only look for mate again when the position may have changed.
+    if map.is_changed() {
+        *mated = map.game.position_result().is_some();
+    }
+
+    if computer_player.is_thinking()
+        || !game_settings.is_computer(map.game.turn)
+        || *mated
+        || map.game.time_result().is_some()
+    {
+        return;
+    }
+
+    if computer_player.table.megabytes() != game_settings.table_megabytes {
+        computer_player.table = TranspositionTable::new(game_settings.table_megabytes);
+    }
+
+    let game = map.game.clone();
+    let difficulty = game_settings.difficulty;
+    let table = computer_player.table.clone();
+    computer_player.history = game.history.clone();
+    computer_player.task = Some(AsyncComputeTaskPool::get().spawn(async move {
+        let mut result = search(&game, difficulty.limits(), Some(&table));
+        result.best_move = difficulty.choose_move(&result, &mut fastrand::Rng::new());
+        result
+    }));
+}
+
+/// Plays the computer's move once its search has finished.
+pub fn finish_computer_move(
+    mut commands: Commands,
+    chess_textures: Res<ChessTextures>,
+    game_settings: Res<GameSettings>,
+    history_view: Res<HistoryView>,
+    mut computer_player: ResMut<ComputerPlayer>,
+    mut map: ResMut<Map>,
+    mut highlighted_hexes: ResMut<HighlightedHexes>,
+) {
+    // Wait for the player to come back from past positions before moving.
+    if !history_view.is_live() {
+        return;
+    }
+    if !computer_player
+        .task
+        .as_ref()
+        .is_some_and(|task| task.is_finished())
+    {
+        return;
+    }
+    let Some(task) = computer_player.task.take() else {
+        return;
+    };
+    let result = block_on(task);
+
+    if computer_player.history != map.game.history || !game_settings.is_computer(map.game.turn) {
+        return;
+    }
+    let Some(chess_move) = result.best_move else {
+        return;
+    };
+
+    highlighted_hexes.reset(&mut commands, &map);
+    if let Err(error) = map.play_move(&mut commands, &chess_textures, chess_move) {
+        warn!("{error}");
+    }
+}
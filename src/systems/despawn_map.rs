@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::PromotionPicker,
+    resources::{ComputerPlayer, HighlightedHexes, HistoryView, Map, PendingPromotion},
+};
+
+/// Removes the board, its pieces and anything left over from the game.
+pub fn despawn_map(
+    mut commands: Commands,
+    map: Option<Res<Map>>,
+    pickers: Query<Entity, With<PromotionPicker>>,
+    mut highlighted_hexes: ResMut<HighlightedHexes>,
+    mut pending_promotion: ResMut<PendingPromotion>,
+    mut history_view: ResMut<HistoryView>,
+    mut computer_player: ResMut<ComputerPlayer>,
+) {
+    if let Some(map) = map {
+        for entity in map
+            .tile_entities
+            .values()
+            .chain(map.piece_entities.values())
+        {
+            commands.entity(*entity).despawn_recursive();
+        }
+        commands.remove_resource::<Map>();
+    }
+
+    for entity in pickers.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    *highlighted_hexes = HighlightedHexes::default();
+    *pending_promotion = PendingPromotion::default();
+    *history_view = HistoryView::default();
+    computer_player.task = None;
+    computer_player.history.clear();
+    computer_player.table.clear();
+}
@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+
+use crate::{
+    data::Square,
+    resources::{
+        ChessTextures, GameSettings, GameState, HighlightedHexes, HistoryView, Map,
+        PendingPromotion,
+    },
+    systems::play_selected,
+};
+
+/// Picks up a piece of the side to move and shows where it can be dropped.
+pub fn drag_start(
+    trigger: Trigger<Pointer<DragStart>>,
+    mut commands: Commands,
+    map: Res<Map>,
+    mut highlighted_hexes: ResMut<HighlightedHexes>,
+    pending_promotion: Res<PendingPromotion>,
+    game_state: Res<State<GameState>>,
+    history_view: Res<HistoryView>,
+    game_settings: Res<GameSettings>,
+) {
+    if trigger.event().button != PointerButton::Primary
+        || pending_promotion.is_pending()
+        || *game_state.get() != GameState::Playing
+        || !history_view.is_live()
+        || game_settings.is_computer(map.game.turn)
+    {
+        return;
+    }
+    let Some(square) = map.piece_square(trigger.entity()) else {
+        return;
+    };
+
+    highlighted_hexes.select_piece(&mut commands, &map, square);
+}
+
+/// Moves the selected piece's sprite along with the pointer.
+pub fn drag_piece(
+    trigger: Trigger<Pointer<Drag>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    map: Res<Map>,
+    highlighted_hexes: Res<HighlightedHexes>,
+    mut transforms: Query<&mut Transform>,
+) {
+    let entity = trigger.entity();
+    let Some(square) = map.piece_square(entity) else {
+        return;
+    };
+    if highlighted_hexes.selected != Some(square) {
+        return;
+    }
+
+    let (camera, camera_transform) = camera.into_inner();
+    let Ok(position) =
+        camera.viewport_to_world_2d(camera_transform, trigger.pointer_location.position)
+    else {
+        return;
+    };
+
+    if let Ok(mut transform) = transforms.get_mut(entity) {
+        // Above the other pieces while it is carried.
+        transform.translation = position.extend(1.0);
+    }
+}
+
+/// Plays the dragged piece onto the hex it was dropped on, or puts it back
+/// where it came from when that is not a legal move.
+pub fn drag_end(
+    trigger: Trigger<Pointer<DragEnd>>,
+    mut commands: Commands,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    chess_textures: Res<ChessTextures>,
+    mut map: ResMut<Map>,
+    mut highlighted_hexes: ResMut<HighlightedHexes>,
+    mut pending_promotion: ResMut<PendingPromotion>,
+    game_state: Res<State<GameState>>,
+) {
+    let entity = trigger.entity();
+    let Some(from) = map.piece_square(entity) else {
+        return;
+    };
+    if highlighted_hexes.selected != Some(from) {
+        return;
+    }
+
+    let (camera, camera_transform) = camera.into_inner();
+    let dropped = camera
+        .viewport_to_world_2d(camera_transform, trigger.pointer_location.position)
+        .ok()
+        .and_then(|position| Square::new(map.game.board.layout.world_pos_to_hex(position)));
+
+    // Pieces dropped after the game was paused or ended go back.
+    if let Some(to) = dropped.filter(|_| *game_state.get() == GameState::Playing) {
+        if play_selected(
+            &mut commands,
+            &chess_textures,
+            &mut map,
+            &mut highlighted_hexes,
+            &mut pending_promotion,
+            to,
+        ) {
+            return;
+        }
+    }
+
+    let position = map.game.board.layout.hex_to_world_pos(from.hex());
+    commands
+        .entity(entity)
+        .insert(Transform::from_xyz(position.x, position.y, 0.0));
+    highlighted_hexes.reset(&mut commands, &map);
+}
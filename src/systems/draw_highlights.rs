@@ -10,8 +10,8 @@ pub fn draw_highlights(
 ) {
     highlighted_hexes.draw(&mut commands, &map);
 
-    for coord in highlighted_hexes.path.iter() {
-        for [start, end] in map.board.layout.all_edge_coordinates(*coord) {
+    for square in highlighted_hexes.path.iter() {
+        for [start, end] in map.game.board.layout.all_edge_coordinates(square.hex()) {
             gizmos.line_2d(start, end, Color::WHITE);
         }
     }
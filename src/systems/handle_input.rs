@@ -1 +1,104 @@
-pub fn handle_input() {}
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    components::PieceType,
+    data::{ChessBoard, ChessMove, Square},
+    resources::{
+        ChessTextures, GameSettings, HighlightedHexes, HistoryView, Map, PendingPromotion,
+    },
+    systems::{get_hovered_square, spawn_promotion_picker},
+};
+
+pub fn handle_input(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    window: Option<Single<&Window, With<PrimaryWindow>>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    chess_textures: Res<ChessTextures>,
+    mut map: ResMut<Map>,
+    mut highlighted_hexes: ResMut<HighlightedHexes>,
+    mut pending_promotion: ResMut<PendingPromotion>,
+    history_view: Res<HistoryView>,
+    game_settings: Res<GameSettings>,
+) {
+    // Past positions shown from the move list are read only, and the
+    // computer's pieces are not the player's to move.
+    if !mouse.just_pressed(MouseButton::Left)
+        || pending_promotion.is_pending()
+        || !history_view.is_live()
+        || game_settings.is_computer(map.game.turn)
+    {
+        return;
+    }
+    let Some(window) = window else {
+        return;
+    };
+    let window = window.into_inner();
+    let (camera, camera_transform) = camera.into_inner();
+
+    let clicked = get_hovered_square(window, camera, camera_transform, &map);
+
+    if let Some(to) = clicked {
+        if play_selected(
+            &mut commands,
+            &chess_textures,
+            &mut map,
+            &mut highlighted_hexes,
+            &mut pending_promotion,
+            to,
+        ) {
+            return;
+        }
+    }
+
+    // Pick up a piece of the side to move, anything else drops the selection.
+    match clicked {
+        Some(square) => {
+            highlighted_hexes.select_piece(&mut commands, &map, square);
+        }
+        None => highlighted_hexes.reset(&mut commands, &map),
+    }
+}
+
+/// Plays the selected piece to `to` if it is one of its legal destinations,
+/// returning whether a move was played or is waiting on a promotion choice.
+pub fn play_selected(
+    commands: &mut Commands,
+    chess_textures: &ChessTextures,
+    map: &mut Map,
+    highlighted_hexes: &mut HighlightedHexes,
+    pending_promotion: &mut PendingPromotion,
+    to: Square,
+) -> bool {
+    let Some(from) = highlighted_hexes.selected else {
+        return false;
+    };
+    if !highlighted_hexes.is_destination(to) {
+        return false;
+    }
+
+    let chess_move = ChessMove::new(from, to);
+    highlighted_hexes.reset(commands, map);
+
+    // Promotions wait on the chooser, with the pawn shown on its new hex.
+    if let Some(pawn) = map.game.board.get_piece(from).filter(|piece| {
+        piece.piece_type() == PieceType::Pawn
+            && ChessBoard::is_promotion_square(to, piece.is_black())
+    }) {
+        if let Some(entity) = map.piece_entities.get(&from.hex()).copied() {
+            let position = map.game.board.layout.hex_to_world_pos(to.hex());
+            commands
+                .entity(entity)
+                .insert(Transform::from_xyz(position.x, position.y, 1.0));
+        }
+        pending_promotion.chess_move = Some(chess_move);
+        spawn_promotion_picker(commands, chess_textures, pawn.is_black());
+        return true;
+    }
+
+    if let Err(error) = map.play_move(commands, chess_textures, chess_move) {
+        warn!("{error}");
+    }
+
+    true
+}
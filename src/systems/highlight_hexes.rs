@@ -1,9 +1,8 @@
 use bevy::{prelude::*, window::PrimaryWindow};
-use hexx::Hex;
 
 use crate::{
-    components::ChessPiece,
-    resources::{HighlightedHexes, Map},
+    data::Square,
+    resources::{HighlightedHexes, HistoryView, Map},
 };
 
 pub fn highlight_hexes(
@@ -12,44 +11,49 @@ pub fn highlight_hexes(
     camera: Single<(&Camera, &GlobalTransform)>,
     map: Res<Map>,
     mut highlighted_hexes: ResMut<HighlightedHexes>,
+    history_view: Res<HistoryView>,
 ) {
+    if !history_view.is_live() {
+        return;
+    }
     let Some(window) = window else {
         return;
     };
     let window = window.into_inner();
     let (camera, camera_transform) = camera.into_inner();
 
-    if let Some(hex) = get_hovered_hex(window, camera, camera_transform, &map) {
-        if hex == highlighted_hexes.hovered {
+    if let Some(square) = get_hovered_square(window, camera, camera_transform, &map) {
+        if highlighted_hexes.hovered == Some(square) {
             return;
         }
-        highlighted_hexes.reset(&mut commands, &map);
 
-        for hex in Hex::ZERO.line_to(hex) {
-            highlighted_hexes.add_to_path(hex);
+        // The moves of a selected piece stay shown while the cursor moves.
+        if highlighted_hexes.selected.is_some() {
+            highlighted_hexes.reset_hovered(&mut commands, &map);
+            highlighted_hexes.hover(square);
+            return;
         }
 
-        highlighted_hexes.hover(hex);
+        highlighted_hexes.reset(&mut commands, &map);
+
+        highlighted_hexes.show_moves(&map, square);
+        highlighted_hexes.hover(square);
     }
 }
 
-fn get_hovered_hex(
+pub fn get_hovered_square(
     window: &Window,
     camera: &Camera,
     camera_transform: &GlobalTransform,
     map: &Map,
-) -> Option<Hex> {
+) -> Option<Square> {
     if let Some(pos) = window.cursor_position().and_then(|viewport_position| {
         camera
             .viewport_to_world_2d(camera_transform, viewport_position)
             .ok()
     }) {
-        let hex = map.board.layout.world_pos_to_hex(pos);
-        if map.tile_entities.contains_key(&hex) {
-            Some(hex)
-        } else {
-            None
-        }
+        let hex = map.game.board.layout.world_pos_to_hex(pos);
+        Square::new(hex)
     } else {
         None
     }
@@ -0,0 +1,268 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    components::MenuButton,
+    data::{Difficulty, GameResult, TimeControl, Turn},
+    resources::{AppState, GameSettings, GameState, Map, PendingPromotion},
+    systems::{despawn_map, spawn_map},
+};
+
+/// The clocks the main menu cycles through.
+const TIME_CONTROLS: [Option<TimeControl>; 5] = [
+    None,
+    Some(TimeControl::SuddenDeath {
+        base: Duration::from_secs(5 * 60),
+    }),
+    Some(TimeControl::Fischer {
+        base: Duration::from_secs(3 * 60),
+        increment: Duration::from_secs(2),
+    }),
+    Some(TimeControl::Bronstein {
+        base: Duration::from_secs(5 * 60),
+        delay: Duration::from_secs(3),
+    }),
+    Some(TimeControl::MovesInPeriod {
+        base: Duration::from_secs(10 * 60),
+        moves: 20,
+    }),
+];
+
+pub fn spawn_main_menu(mut commands: Commands, game_settings: Res<GameSettings>) {
+    spawn_menu(
+        &mut commands,
+        StateScoped(AppState::MainMenu),
+        "Hexachess",
+        &[
+            (MenuButton::Play, "Play"),
+            (
+                MenuButton::Opponent,
+                &opponent_label(game_settings.computer),
+            ),
+            (
+                MenuButton::AutoOrient,
+                &auto_orient_label(game_settings.auto_orient),
+            ),
+            (
+                MenuButton::TimeControl,
+                &time_control_label(game_settings.time_control),
+            ),
+            (
+                MenuButton::Difficulty,
+                &difficulty_label(game_settings.difficulty),
+            ),
+        ],
+    );
+}
+
+pub fn spawn_pause_menu(mut commands: Commands) {
+    spawn_menu(
+        &mut commands,
+        StateScoped(GameState::Paused),
+        "Paused",
+        &[
+            (MenuButton::Resume, "Resume"),
+            (MenuButton::MainMenu, "Back to menu"),
+        ],
+    );
+}
+
+pub fn spawn_game_over_screen(mut commands: Commands, map: Res<Map>) {
+    let title = match map.game.result() {
+        Some(GameResult::Checkmate { winner }) => format!("{} wins by checkmate", side(winner)),
+        Some(GameResult::Stalemate { winner }) => {
+            format!("{} wins 3/4 to 1/4 by stalemate", side(winner))
+        }
+        Some(GameResult::Timeout { winner }) => format!("{} wins on time", side(winner)),
+        Some(GameResult::Draw) | None => String::from("Draw"),
+    };
+
+    spawn_menu(
+        &mut commands,
+        StateScoped(GameState::GameOver),
+        &title,
+        &[
+            (MenuButton::Rematch, "Rematch"),
+            (MenuButton::MainMenu, "Back to menu"),
+        ],
+    );
+}
+
+pub fn menu_buttons(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &MenuButton, &Children), Changed<Interaction>>,
+    mut texts: Query<&mut Text>,
+    mut game_settings: ResMut<GameSettings>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button, children) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            MenuButton::Play => next_app_state.set(AppState::InGame),
+            MenuButton::Opponent => {
+                game_settings.computer = match game_settings.computer {
+                    None => Some(Turn::Black),
+                    Some(Turn::Black) => Some(Turn::White),
+                    Some(Turn::White) => None,
+                };
+                let label = opponent_label(game_settings.computer);
+                set_label(&mut texts, children, label);
+            }
+            MenuButton::AutoOrient => {
+                game_settings.auto_orient = !game_settings.auto_orient;
+                let label = auto_orient_label(game_settings.auto_orient);
+                set_label(&mut texts, children, label);
+            }
+            MenuButton::TimeControl => {
+                let current = TIME_CONTROLS
+                    .iter()
+                    .position(|control| *control == game_settings.time_control)
+                    .unwrap_or(0);
+                game_settings.time_control = TIME_CONTROLS[(current + 1) % TIME_CONTROLS.len()];
+                let label = time_control_label(game_settings.time_control);
+                set_label(&mut texts, children, label);
+            }
+            MenuButton::Difficulty => {
+                game_settings.difficulty = game_settings.difficulty.next();
+                let label = difficulty_label(game_settings.difficulty);
+                set_label(&mut texts, children, label);
+            }
+            MenuButton::Resume => next_game_state.set(GameState::Playing),
+            MenuButton::Rematch => {
+                commands.run_system_cached(despawn_map);
+                commands.run_system_cached(spawn_map);
+                next_game_state.set(GameState::Playing);
+            }
+            MenuButton::MainMenu => next_app_state.set(AppState::MainMenu),
+        }
+    }
+}
+
+/// Escape pauses and resumes the game, unless it is closing the promotion
+/// chooser.
+pub fn toggle_pause(
+    keys: Res<ButtonInput<KeyCode>>,
+    game_state: Res<State<GameState>>,
+    pending_promotion: Res<PendingPromotion>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if !keys.just_pressed(KeyCode::Escape) || pending_promotion.is_pending() {
+        return;
+    }
+
+    match game_state.get() {
+        GameState::Playing => next_game_state.set(GameState::Paused),
+        GameState::Paused => next_game_state.set(GameState::Playing),
+        GameState::GameOver => {}
+    }
+}
+
+/// Ends the game on mate, stalemate or a fallen flag. Mate and stalemate
+/// are only looked for again when the map has changed.
+pub fn check_game_over(
+    mut map: ResMut<Map>,
+    mut position_result: Local<Option<GameResult>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if map.is_changed() {
+        *position_result = map.game.position_result();
+    }
+    if position_result.is_none() && map.game.time_result().is_none() {
+        return;
+    }
+
+    if let Some(clock) = map.game.clock.as_mut() {
+        clock.stop();
+    }
+    next_game_state.set(GameState::GameOver);
+}
+
+fn opponent_label(computer: Option<Turn>) -> String {
+    match computer {
+        Some(turn) => format!("Computer plays {}", side(turn)),
+        None => String::from("Two players"),
+    }
+}
+
+fn auto_orient_label(auto_orient: bool) -> String {
+    let state = if auto_orient { "on" } else { "off" };
+    format!("Auto-orient board: {state}")
+}
+
+fn time_control_label(time_control: Option<TimeControl>) -> String {
+    match time_control {
+        Some(time_control) => format!("Clock: {time_control}"),
+        None => String::from("Clock: none"),
+    }
+}
+
+fn difficulty_label(difficulty: Difficulty) -> String {
+    format!("Difficulty: {difficulty}")
+}
+
+fn set_label(texts: &mut Query<&mut Text>, children: &Children, label: String) {
+    let mut labels = texts.iter_many_mut(children);
+    while let Some(mut text) = labels.fetch_next() {
+        text.0 = label.clone();
+    }
+}
+
+fn side(turn: Turn) -> &'static str {
+    match turn {
+        Turn::White => "White",
+        Turn::Black => "Black",
+    }
+}
+
+fn spawn_menu(
+    commands: &mut Commands,
+    scope: impl Bundle,
+    title: &str,
+    buttons: &[(MenuButton, &str)],
+) {
+    commands
+        .spawn((
+            scope,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..Default::default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(title),
+                TextFont {
+                    font_size: 40.0,
+                    ..Default::default()
+                },
+            ));
+
+            for (button, label) in buttons {
+                parent
+                    .spawn((
+                        *button,
+                        Button,
+                        Node {
+                            width: Val::Px(300.0),
+                            padding: UiRect::all(Val::Px(8.0)),
+                            justify_content: JustifyContent::Center,
+                            ..Default::default()
+                        },
+                        BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+                    ))
+                    .with_child(Text::new(*label));
+            }
+        });
+}
@@ -1,3 +1,15 @@
+mod clocks;
+pub use self::clocks::*;
+
+mod computer_move;
+pub use self::computer_move::*;
+
+mod despawn_map;
+pub use self::despawn_map::*;
+
+mod drag_piece;
+pub use self::drag_piece::*;
+
 mod draw_highlights;
 pub use self::draw_highlights::*;
 
@@ -7,8 +19,26 @@ pub use self::handle_input::*;
 mod highlight_hexes;
 pub use self::highlight_hexes::*;
 
+mod menus;
+pub use self::menus::*;
+
+mod move_list;
+pub use self::move_list::*;
+
+mod orient_board;
+pub use self::orient_board::*;
+
+mod persistent_highlights;
+pub use self::persistent_highlights::*;
+
+mod promotion_picker;
+pub use self::promotion_picker::*;
+
 mod spawn_camera;
 pub use self::spawn_camera::*;
 
 mod spawn_map;
 pub use self::spawn_map::*;
+
+mod undo_redo;
+pub use self::undo_redo::*;
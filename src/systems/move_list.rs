@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{FlipButton, HistoryButton, MoveList, MoveListEntry},
+    resources::{AppState, ChessTextures, HighlightedHexes, HistoryView, Map, PendingPromotion},
+    systems::respawn_pieces,
+};
+
+const ENTRY_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+const VIEWED_ENTRY_COLOR: Color = Color::srgb(0.2, 0.4, 0.7);
+
+/// Spawns the panel on the right of the board that lists the moves.
+pub fn spawn_move_list(mut commands: Commands) {
+    commands
+        .spawn((
+            StateScoped(AppState::InGame),
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(0.0),
+                top: Val::Px(0.0),
+                width: Val::Px(240.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                row_gap: Val::Px(8.0),
+                ..Default::default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
+        ))
+        .with_children(|parent| {
+            parent.spawn(Text::new("Moves"));
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(8.0),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for (button, label) in
+                        [(HistoryButton::Undo, "Undo"), (HistoryButton::Redo, "Redo")]
+                    {
+                        parent
+                            .spawn((
+                                button,
+                                Button,
+                                Node {
+                                    padding: UiRect::axes(Val::Px(12.0), Val::Px(4.0)),
+                                    ..Default::default()
+                                },
+                                BackgroundColor(ENTRY_COLOR),
+                            ))
+                            .with_child(Text::new(label));
+                    }
+                    parent
+                        .spawn((
+                            FlipButton,
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(12.0), Val::Px(4.0)),
+                                ..Default::default()
+                            },
+                            BackgroundColor(ENTRY_COLOR),
+                        ))
+                        .with_child(Text::new("Flip"));
+                });
+            parent.spawn((
+                MoveList,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(2.0),
+                    overflow: Overflow::scroll_y(),
+                    ..Default::default()
+                },
+            ));
+        });
+}
+
+/// Rewrites the move list in Glinski notation, one row per move pair.
+pub fn update_move_list(
+    mut commands: Commands,
+    map: Res<Map>,
+    history_view: Res<HistoryView>,
+    list: Single<Entity, With<MoveList>>,
+) {
+    if !map.is_changed() && !history_view.is_changed() {
+        return;
+    }
+
+    let sans = map.game.history_san();
+    let viewed = history_view.ply.unwrap_or(sans.len());
+
+    let mut rows: Vec<(u32, Vec<(usize, &str)>)> = Vec::new();
+    let mut number = map.game.start_fullmove_number;
+    let mut black = map.game.start_turn.is_black();
+    for (index, san) in sans.iter().enumerate() {
+        if !black || rows.is_empty() {
+            rows.push((number, Vec::new()));
+        }
+        if let Some((_, moves)) = rows.last_mut() {
+            moves.push((index + 1, san));
+        }
+        if black {
+            number += 1;
+        }
+        black = !black;
+    }
+
+    let list = list.into_inner();
+    commands.entity(list).despawn_descendants();
+    commands.entity(list).with_children(|parent| {
+        for (number, moves) in rows {
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(4.0),
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(format!("{number}.")),
+                        Node {
+                            width: Val::Px(40.0),
+                            ..Default::default()
+                        },
+                    ));
+                    for (ply, san) in moves {
+                        let color = if ply == viewed {
+                            VIEWED_ENTRY_COLOR
+                        } else {
+                            ENTRY_COLOR
+                        };
+                        parent
+                            .spawn((
+                                MoveListEntry(ply),
+                                Button,
+                                Node {
+                                    width: Val::Px(80.0),
+                                    padding: UiRect::horizontal(Val::Px(4.0)),
+                                    ..Default::default()
+                                },
+                                BackgroundColor(color),
+                            ))
+                            .with_child(Text::new(san));
+                    }
+                });
+        }
+    });
+}
+
+/// Clicking a move shows the position after it, the left and right arrow
+/// keys step through the game one move at a time.
+pub fn move_list_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    entries: Query<(&Interaction, &MoveListEntry), Changed<Interaction>>,
+    map: Res<Map>,
+    pending_promotion: Res<PendingPromotion>,
+    mut history_view: ResMut<HistoryView>,
+) {
+    if pending_promotion.is_pending() {
+        return;
+    }
+
+    let len = map.game.history.len();
+    let current = history_view.ply.unwrap_or(len);
+
+    if let Some((_, entry)) = entries
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        history_view.show(entry.0, len);
+    } else if keys.just_pressed(KeyCode::ArrowLeft) && current > 0 {
+        history_view.show(current - 1, len);
+    } else if keys.just_pressed(KeyCode::ArrowRight) && current < len {
+        history_view.show(current + 1, len);
+    }
+}
+
+/// Puts the pieces of the position picked in the move list on the board.
+pub fn show_history_position(
+    mut commands: Commands,
+    chess_textures: Res<ChessTextures>,
+    history_view: Res<HistoryView>,
+    mut map: ResMut<Map>,
+    mut highlighted_hexes: ResMut<HighlightedHexes>,
+) {
+    if !history_view.is_changed() {
+        return;
+    }
+
+    let board = match history_view.ply {
+        Some(ply) => map.game.at_ply(ply).board,
+        None => map.game.board.clone(),
+    };
+
+    highlighted_hexes.reset(&mut commands, &map);
+    respawn_pieces(&mut commands, &chess_textures, &mut map, &board);
+}
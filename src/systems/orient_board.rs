@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{FlipButton, Upright},
+    resources::{BoardOrientation, GameSettings, PendingPromotion},
+};
+
+/// Flips the board with the F key or the flip button.
+pub fn flip_board(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<FlipButton>)>,
+    pending_promotion: Res<PendingPromotion>,
+    mut orientation: ResMut<BoardOrientation>,
+) {
+    let pressed = buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    if (pressed || keys.just_pressed(KeyCode::KeyF)) && !pending_promotion.is_pending() {
+        orientation.flipped = !orientation.flipped;
+    }
+}
+
+/// Puts the human player's side at the bottom when a game against the
+/// computer starts.
+pub fn auto_orient_board(
+    game_settings: Res<GameSettings>,
+    mut orientation: ResMut<BoardOrientation>,
+) {
+    if game_settings.auto_orient {
+        if let Some(computer) = game_settings.computer {
+            orientation.flipped = !computer.is_black();
+        }
+    }
+}
+
+/// Turns the camera to the board orientation, and turns pieces and labels
+/// with it so that they are not drawn upside down.
+pub fn orient_board(
+    orientation: Res<BoardOrientation>,
+    mut camera: Single<&mut Transform, With<Camera2d>>,
+    mut uprights: Query<&mut Transform, (With<Upright>, Without<Camera2d>)>,
+) {
+    let rotation = orientation.rotation();
+    if camera.rotation != rotation {
+        camera.rotation = rotation;
+    }
+
+    // Moved pieces get a fresh transform, so this is checked every frame.
+    for mut transform in uprights.iter_mut() {
+        if transform.rotation != rotation {
+            transform.rotation = rotation;
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+use crate::resources::{HighlightedHexes, HistoryView, Map};
+
+/// Marks the last move and a king in check for the position on the board.
+pub fn persistent_highlights(
+    mut commands: Commands,
+    map: Res<Map>,
+    history_view: Res<HistoryView>,
+    mut highlighted_hexes: ResMut<HighlightedHexes>,
+) {
+    if !map.is_changed() && !history_view.is_changed() {
+        return;
+    }
+
+    let viewed;
+    let game = match history_view.ply {
+        Some(ply) => {
+            viewed = map.game.at_ply(ply);
+            &viewed
+        }
+        None => &map.game,
+    };
+
+    let last_move = game.last_move().map(|undo| (undo.from, undo.to));
+    let check = if game.is_in_check() {
+        game.board.king_square(game.turn.is_black())
+    } else {
+        None
+    };
+
+    highlighted_hexes.set_persistent(&mut commands, &map, last_move, check);
+}
@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{ChessPiece, PieceType, PromotionButton, PromotionCancel, PromotionPicker},
+    resources::{ChessTextures, Map, PendingPromotion},
+};
+
+/// Shows a chooser over the board with the promotion pieces of the mover's
+/// colour and a button to take the move back.
+pub fn spawn_promotion_picker(
+    commands: &mut Commands,
+    chess_textures: &ChessTextures,
+    black: bool,
+) {
+    commands
+        .spawn((
+            PromotionPicker,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(12.0)),
+                        row_gap: Val::Px(12.0),
+                        ..Default::default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                ))
+                .with_children(|parent| {
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(8.0),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            for piece_type in PieceType::PROMOTIONS {
+                                let piece = ChessPiece::pawn(black).with_type(piece_type);
+                                parent
+                                    .spawn((
+                                        PromotionButton(piece_type),
+                                        Button,
+                                        Node {
+                                            width: Val::Px(72.0),
+                                            height: Val::Px(72.0),
+                                            padding: UiRect::all(Val::Px(4.0)),
+                                            ..Default::default()
+                                        },
+                                        BackgroundColor(Color::srgb(0.5, 0.5, 0.5)),
+                                    ))
+                                    .with_child(chess_textures.image_node(piece));
+                            }
+                        });
+
+                    parent
+                        .spawn((
+                            PromotionCancel,
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(16.0), Val::Px(6.0)),
+                                ..Default::default()
+                            },
+                            BackgroundColor(Color::srgb(0.5, 0.5, 0.5)),
+                        ))
+                        .with_child(Text::new("Cancel"));
+                });
+        });
+}
+
+/// Plays the pending promotion once a piece is chosen, or puts the pawn back
+/// on cancel or escape.
+pub fn promotion_picker(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    pieces: Query<(&Interaction, &PromotionButton), Changed<Interaction>>,
+    cancels: Query<&Interaction, (Changed<Interaction>, With<PromotionCancel>)>,
+    pickers: Query<Entity, With<PromotionPicker>>,
+    chess_textures: Res<ChessTextures>,
+    mut map: ResMut<Map>,
+    mut pending_promotion: ResMut<PendingPromotion>,
+) {
+    let Some(chess_move) = pending_promotion.chess_move else {
+        return;
+    };
+
+    let choice = pieces
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| button.0);
+    let cancelled = keys.just_pressed(KeyCode::Escape)
+        || cancels
+            .iter()
+            .any(|interaction| *interaction == Interaction::Pressed);
+    if choice.is_none() && !cancelled {
+        return;
+    }
+
+    pending_promotion.chess_move = None;
+    for entity in pickers.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    match choice {
+        Some(piece_type) => {
+            let chess_move = chess_move.with_promotion(piece_type);
+            if let Err(error) = map.play_move(&mut commands, &chess_textures, chess_move) {
+                warn!("{error}");
+            }
+        }
+        None => {
+            if let Some(entity) = map.piece_entities.get(&chess_move.from.hex()).copied() {
+                let position = map
+                    .game
+                    .board
+                    .layout
+                    .hex_to_world_pos(chess_move.from.hex());
+                commands
+                    .entity(entity)
+                    .insert(Transform::from_xyz(position.x, position.y, 0.0));
+            }
+        }
+    }
+}
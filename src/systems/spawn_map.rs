@@ -3,14 +3,15 @@ use bevy::{
     color::palettes::css,
     prelude::*,
     render::mesh::{Indices, PrimitiveTopology},
-    sprite::Anchor,
     utils::HashMap,
 };
-use hexx::{shapes, Hex, HexLayout, PlaneMeshBuilder};
+use hexx::{Hex, PlaneMeshBuilder};
 
 use crate::{
-    data::ChessBoard,
-    resources::{ChessTextures, Map, TileColor},
+    components::Upright,
+    data::{ChessBoard, ChessClock, Game, Square},
+    resources::{ChessTextures, GameSettings, Map, TileColor},
+    systems::{drag_end, drag_piece, drag_start},
 };
 
 const HEX_SIZE: f32 = 50.0;
@@ -18,10 +19,17 @@ const HEX_SIZE: f32 = 50.0;
 pub fn spawn_map(
     mut commands: Commands,
     chess_textures: Res<ChessTextures>,
+    game_settings: Res<GameSettings>,
     mut a_meshes: ResMut<Assets<Mesh>>,
     mut a_materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    let board = ChessBoard::new(HEX_SIZE);
+    let mut game = Game::new(ChessBoard::new(HEX_SIZE));
+    if let Some(time_control) = game_settings.time_control {
+        let mut clock = ChessClock::new(time_control);
+        clock.start(game.turn);
+        game.clock = Some(clock);
+    }
+    let board = &game.board;
 
     let tile_black_material = a_materials.add(Color::Srgba(css::BLACK));
     let tile_grey_material = a_materials.add(Color::Srgba(css::GREY));
@@ -30,6 +38,8 @@ pub fn spawn_map(
     let hovered_material = a_materials.add(Color::Srgba(css::BLUE));
     let path_material = a_materials.add(Color::Srgba(css::ORANGE));
     let attack_material = a_materials.add(Color::Srgba(css::RED));
+    let last_move_material = a_materials.add(Color::Srgba(css::OLIVE));
+    let check_material = a_materials.add(Color::Srgba(css::CRIMSON));
 
     let mesh_info = PlaneMeshBuilder::new(&board.layout)
         .facing(Vec3::Z)
@@ -48,32 +58,11 @@ pub fn spawn_map(
 
     let mesh_handle = a_meshes.add(mesh);
 
-    let mut piece_entities = HashMap::new();
-    for (hex, maybe_piece) in board.pieces.iter() {
-        if let Some(piece) = maybe_piece {
-            let position = board.layout.hex_to_world_pos(*hex);
-            let id = commands
-                .spawn((
-                    *piece,
-                    Sprite {
-                        image: chess_textures.handle.clone_weak(),
-                        texture_atlas: Some(TextureAtlas {
-                            layout: chess_textures.layout.clone_weak(),
-                            index: piece.image_index(),
-                        }),
-                        custom_size: Some(Vec2::splat(64.0)),
-                        anchor: Anchor::Custom(Vec2::new(0.0, -0.2)),
-                        ..Default::default()
-                    },
-                    Transform::from_xyz(position.x, position.y, 0.0),
-                ))
-                .id();
-            piece_entities.insert(*hex, id);
-        }
-    }
+    let piece_entities = spawn_pieces(&mut commands, &chess_textures, board);
 
-    let tile_entities = shapes::hexagon(Hex::ZERO, 5)
-        .map(|hex| {
+    let tile_entities = Square::all()
+        .map(|square| {
+            let hex = square.hex();
             let color = match Map::tile_color(hex) {
                 TileColor::Grey => tile_grey_material.clone_weak(),
                 TileColor::Black => tile_black_material.clone_weak(),
@@ -88,13 +77,14 @@ pub fn spawn_map(
                 ))
                 .with_children(|parent| {
                     parent.spawn((
-                        Text2d(ChessBoard::to_glinski_notation(hex).unwrap()),
+                        Text2d(square.to_string()),
                         TextColor(Color::Srgba(css::REBECCA_PURPLE)),
                         TextFont {
                             font_size: 14.0,
                             ..Default::default()
                         },
                         Transform::from_xyz(0.0, -32.0, 10.0),
+                        Upright,
                     ));
                 })
                 .id();
@@ -103,7 +93,7 @@ pub fn spawn_map(
         .collect();
 
     commands.insert_resource(Map {
-        board,
+        game,
         tile_entities,
         piece_entities,
         tile_black_material,
@@ -112,6 +102,45 @@ pub fn spawn_map(
         hovered_material,
         path_material,
         attack_material,
+        last_move_material,
+        check_material,
         // default_material,
     });
 }
+
+/// Spawns a draggable sprite for every piece on `board`, keyed by hex.
+pub fn spawn_pieces(
+    commands: &mut Commands,
+    chess_textures: &ChessTextures,
+    board: &ChessBoard,
+) -> HashMap<Hex, Entity> {
+    let mut piece_entities = HashMap::new();
+    for (square, piece) in board.pieces() {
+        let position = board.layout.hex_to_world_pos(square.hex());
+        let id = commands
+            .spawn((
+                piece,
+                chess_textures.sprite(piece),
+                Transform::from_xyz(position.x, position.y, 0.0),
+                Upright,
+            ))
+            .observe(drag_start)
+            .observe(drag_piece)
+            .observe(drag_end)
+            .id();
+        piece_entities.insert(square.hex(), id);
+    }
+
+    piece_entities
+}
+
+/// Replaces the piece sprites in `map` with ones for the pieces on `board`.
+pub fn respawn_pieces(
+    commands: &mut Commands,
+    chess_textures: &ChessTextures,
+    map: &mut Map,
+    board: &ChessBoard,
+) {
+    map.despawn_pieces(commands);
+    map.piece_entities = spawn_pieces(commands, chess_textures, board);
+}
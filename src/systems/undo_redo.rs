@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::HistoryButton,
+    resources::{
+        ChessTextures, GameSettings, HighlightedHexes, HistoryView, Map, PendingPromotion,
+    },
+    systems::respawn_pieces,
+};
+
+/// Takes back moves with ctrl+z or the undo button and replays them with
+/// ctrl+y, ctrl+shift+z or the redo button. Against the computer both walk a
+/// whole move pair so that it is the player's turn again.
+pub fn undo_redo(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Query<(&Interaction, &HistoryButton), Changed<Interaction>>,
+    chess_textures: Res<ChessTextures>,
+    game_settings: Res<GameSettings>,
+    history_view: Res<HistoryView>,
+    pending_promotion: Res<PendingPromotion>,
+    mut map: ResMut<Map>,
+    mut highlighted_hexes: ResMut<HighlightedHexes>,
+) {
+    if !history_view.is_live() || pending_promotion.is_pending() {
+        return;
+    }
+
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let pressed = buttons
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button);
+
+    let action = match pressed {
+        Some(button) => button,
+        None if ctrl && keys.just_pressed(KeyCode::KeyZ) && !shift => HistoryButton::Undo,
+        None if ctrl && (keys.just_pressed(KeyCode::KeyY) || keys.just_pressed(KeyCode::KeyZ)) => {
+            HistoryButton::Redo
+        }
+        None => return,
+    };
+
+    let game = &mut map.game;
+    let changed = match action {
+        HistoryButton::Undo => {
+            let taken_back = game.take_back().is_some();
+            if taken_back && game_settings.is_computer(game.turn) {
+                game.take_back();
+            }
+            taken_back
+        }
+        HistoryButton::Redo => match game.redo() {
+            Ok(Some(_)) => {
+                if game_settings.is_computer(game.turn) {
+                    if let Err(error) = game.redo() {
+                        warn!("{error}");
+                    }
+                }
+                true
+            }
+            Ok(None) => false,
+            Err(error) => {
+                warn!("{error}");
+                false
+            }
+        },
+    };
+
+    if changed {
+        let board = map.game.board.clone();
+        highlighted_hexes.reset(&mut commands, &map);
+        respawn_pieces(&mut commands, &chess_textures, &mut map, &board);
+    }
+}